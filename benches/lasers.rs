@@ -0,0 +1,23 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use physics_rust::bench::PrismScene;
+
+fn prisms(c: &mut Criterion) {
+    let mut group = c.benchmark_group("prisms");
+    for lasers in [1, 4, 16] {
+        let mut scene = PrismScene::new(lasers, 4);
+        group.bench_with_input(BenchmarkId::new("traced", lasers), &lasers, |b, _| {
+            b.iter(|| scene.trace())
+        });
+        group.bench_with_input(BenchmarkId::new("cached", lasers), &lasers, |b, _| {
+            b.iter(|| scene.update())
+        });
+        let mut scene = PrismScene::new(lasers, 4).with_moving_body();
+        group.bench_with_input(BenchmarkId::new("moving unrelated body", lasers), &lasers, |b, _| {
+            b.iter(|| scene.update())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, prisms);
+criterion_main!(benches);
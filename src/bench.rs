@@ -0,0 +1,104 @@
+//! Scenes used by the benchmarks in `benches/`
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::config::AppConfig;
+use crate::measures::SimulationTime;
+use crate::objects::laser::{trace_lasers, LaserBundle, LaserCache, LaserHitEvent, Spectrum};
+use crate::objects::phy_obj::PhysicalObject;
+
+/// White lasers shining through a row of glass prisms
+pub struct PrismScene {
+    app: App,
+    schedule: Schedule,
+    /// Body far from the lasers, moved on every update
+    moving: Option<Entity>,
+}
+
+impl PrismScene {
+    pub fn new(lasers: usize, prisms: usize) -> Self {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            HierarchyPlugin,
+            RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(1.0),
+        ))
+        .insert_resource(RapierConfiguration {
+            physics_pipeline_active: false,
+            ..Default::default()
+        })
+        .init_resource::<AppConfig>()
+        .init_resource::<SimulationTime>()
+        .init_resource::<LaserCache>()
+        .add_event::<LaserHitEvent>();
+
+        for i in 0..prisms {
+            let prism = PhysicalObject::poly(
+                vec![
+                    Vec2::new(-1.0, -1.0),
+                    Vec2::new(1.0, -1.0),
+                    Vec2::new(0.0, 1.0),
+                ],
+                Vec3::new(i as f32 * 3.0, 0.0, 1.0),
+            );
+            app.world.spawn(prism).insert(RigidBody::Fixed);
+        }
+
+        for i in 0..lasers {
+            let angle = -0.2 + 0.4 * i as f32 / lasers.max(1) as f32;
+            app.world.spawn((
+                LaserBundle {
+                    fade_distance: prisms as f32 * 3.0 + 10.0,
+                    ..LaserBundle::new(Spectrum::White, 0.05)
+                },
+                TransformBundle::from_transform(
+                    Transform::from_xyz(-3.0, -0.2, 2.0).with_rotation(Quat::from_rotation_z(angle)),
+                ),
+                Collider::cuboid(0.5, 0.25),
+                Sensor,
+            ));
+        }
+
+        // let the physics engine pick up the colliders
+        app.update();
+        app.update();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(trace_lasers);
+        Self {
+            app,
+            schedule,
+            moving: None,
+        }
+    }
+
+    /// Adds a ball away from the lasers, like in a running scene where the physics engine moves
+    /// bodies the rays never reach.
+    pub fn with_moving_body(mut self) -> Self {
+        let ball = PhysicalObject::ball(0.5, Vec3::new(1.0, 50.0, 1.0));
+        self.moving = Some(self.app.world.spawn(ball).id());
+        self.app.update();
+        self.app.update();
+        self
+    }
+
+    /// Traces every laser from scratch, returns the number of rays.
+    pub fn trace(&mut self) -> usize {
+        self.app.world.resource_mut::<LaserCache>().invalidate();
+        self.update()
+    }
+
+    /// Runs the tracing system, which reuses the rays of the previous run when nothing they reach
+    /// moved.
+    pub fn update(&mut self) -> usize {
+        if let Some(body) = self.moving {
+            // back and forth, so that it stays away from the lasers however long the bench runs
+            let mut xform = self.app.world.get_mut::<Transform>(body).unwrap();
+            xform.translation.x = -xform.translation.x;
+        }
+        self.schedule.run(&mut self.app.world);
+        self.app.world.resource::<LaserCache>().ray_count()
+    }
+}
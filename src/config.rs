@@ -1,11 +1,23 @@
 use bevy::prelude::Resource;
 
+use crate::objects::laser::FresnelModel;
+
 #[derive(Resource, Copy, Clone)]
 pub struct AppConfig {
     pub ui_scale: i32,
     pub zoom_speed: f32,
     pub tool_cursor: bool,
-    pub kinetic_panning: bool
+    pub kinetic_panning: bool,
+    /// m/N
+    pub force_scale: f32,
+    /// m per m/s
+    pub velocity_scale: f32,
+    /// m per kg·m/s
+    pub momentum_scale: f32,
+    pub vector_labels: bool,
+    pub fresnel: FresnelModel,
+    /// Draw the normals and the angles of the rays at each interface
+    pub laser_angles: bool,
 }
 
 impl Default for AppConfig {
@@ -14,7 +26,13 @@ impl Default for AppConfig {
             ui_scale: 100,
             zoom_speed: 1.0,
             tool_cursor: true,
-            kinetic_panning: true
+            kinetic_panning: true,
+            force_scale: 0.05,
+            velocity_scale: 0.2,
+            momentum_scale: 0.1,
+            vector_labels: true,
+            fresnel: FresnelModel::default(),
+            laser_angles: false,
         }
     }
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
 use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
+use bevy::render::mesh::PrimitiveTopology;
 use bevy::render::texture::ImageSampler;
 use bevy::utils::{HashMap, HashSet};
 
@@ -17,7 +18,7 @@ pub use egui::egui_assert;
 use crate::skin::SkinConfig;
 use mouse::{button, wheel};
 use objects::hinge::HingeObject;
-use objects::laser::LaserRays;
+use objects::laser::{LaserCache, LaserHitEvent, LaserRays};
 use objects::{laser, ColorComponent, SettingComponent};
 use palette::{PaletteConfig, PaletteList, PaletteLoader};
 use tools::add_object::AddObjectEvent;
@@ -35,6 +36,8 @@ use crate::config::AppConfig;
 use crate::mouse::r#move::{MouseLongOrMoved, MouseLongOrMovedWriteback};
 use crate::mouse::select::{SelectEvent, SelectUnderMouseEvent};
 use crate::objects::SpriteOnly;
+use crate::measures::SimulationTime;
+use crate::scripting::ScriptEngine;
 use crate::tools::drag::{DragConfig, DragEvent};
 use crate::tools::r#move::MoveEvent;
 use crate::tools::ToolIcons;
@@ -46,12 +49,15 @@ mod measures;
 mod mouse;
 mod objects;
 mod palette;
+mod scripting;
 mod tools;
 mod ui;
 mod update_from;
 mod skin;
 mod config;
 //mod grid;
+#[cfg(feature = "bench")]
+pub mod bench;
 
 const BORDER_THICKNESS: f32 = 0.03;
 const CAMERA_FAR: f32 = 1e6f32;
@@ -195,6 +201,9 @@ pub fn app_main() {
         .init_resource::<SkinConfig>()
         .init_resource::<AppConfig>()
         .init_resource::<DragConfig>()
+        .init_resource::<ScriptEngine>()
+        .init_resource::<SimulationTime>()
+        .init_resource::<LaserCache>()
         .insert_resource(RapierConfiguration {
             gravity: Vect::Y * -9.81,
             physics_pipeline_active: false,
@@ -225,6 +234,7 @@ pub fn app_main() {
         .add_event::<SelectEvent>()
         .add_event::<ContextMenuEvent>()
         .add_event::<RemoveTemporaryWindowsEvent>()
+        .add_event::<LaserHitEvent>()
         .add_systems(
             Startup,
             (
@@ -238,6 +248,11 @@ pub fn app_main() {
         .add_systems(Update, update_from_palette);
     ui::add_systems(&mut app);
     measures::add_systems(&mut app);
+    app.add_systems(
+        PostUpdate,
+        SimulationTime::advance.after(PhysicsSet::StepSimulation),
+    );
+    scripting::add_systems(&mut app);
     app.add_systems(
         Update,
         (
@@ -288,7 +303,16 @@ pub fn app_main() {
             .after(cursor::check_egui_wants_focus),
     )
     .add_systems(Update, update_draw_modes)
-    .add_systems(Update, laser::draw_lasers)
+    .add_systems(
+        Update,
+        (
+            laser::trace_lasers,
+            laser::draw_lasers,
+            laser::draw_laser_angles,
+            laser::inspect_laser_rays,
+        )
+            .chain(),
+    )
     .add_systems(Update, apply_custom_forces);
     //.add_systems(PostUpdate, despawn_entities)
     // ;
@@ -388,7 +412,11 @@ fn process_unfreeze_entity(
 #[derive(Component)]
 pub struct UiCamera;
 
-fn setup_graphics(mut commands: Commands) {
+fn setup_graphics(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
     info!("Setting up graphics");
     // Add a camera so we can see the debug-render.
     // note: camera's scale means meters per pixel
@@ -411,11 +439,15 @@ fn setup_graphics(mut commands: Commands) {
     cursor_bundle.style.margin = UiRect::px(12.0, 0.0, 16.0, 0.0);
     commands.spawn((ToolCursor, cursor_bundle));
 
+    // every ray is drawn in this mesh, with the color of its vertices
     commands.spawn((
         LaserRays::default(),
-        Visibility::Visible,
-        ComputedVisibility::default(),
-        TransformBundle::default(),
+        ColorMesh2dBundle {
+            mesh: meshes.add(Mesh::new(PrimitiveTopology::TriangleList)).into(),
+            material: materials.add(ColorMaterial::default()),
+            visibility: Visibility::Hidden,
+            ..Default::default()
+        },
     ));
 }
 
@@ -465,6 +497,22 @@ fn make_stroke(color: Color, thickness: f32) -> Stroke {
 
 const STROKE_TOLERANCE: f32 = 0.0001;
 
+/// Draws an arrow in the foreground, the head being proportional to its length.
+pub(crate) fn draw_arrow(gizmos: &mut Gizmos, from: Vec2, to: Vec2, color: Color) {
+    let dir = to - from;
+    let length = dir.length();
+    if length < 1e-4 {
+        return;
+    }
+    let head = dir / length * (length * 0.25).min(0.2);
+    let z = FOREGROUND_Z;
+    gizmos.line(from.extend(z), to.extend(z), color);
+    for side in [-1.0, 1.0] {
+        let back = to - head + head.perp() * 0.5 * side;
+        gizmos.line(to.extend(z), back.extend(z), color);
+    }
+}
+
 fn setup_physics(mut images: ResMut<Assets<Image>>) {
 
 }
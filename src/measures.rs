@@ -1,12 +1,63 @@
-use crate::systems;
+use crate::{CustomForce, CustomForceDespawn, InvTransformPoint};
+use bevy::math::Vec3Swizzles;
 use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+use bevy::utils::HashMap;
 use bevy_rapier2d::prelude::*;
+use bevy_rapier2d::rapier::dynamics::JointAxesMask;
+use strum::IntoStaticStr;
 
-systems! {
-    KineticEnergy::compute,
-    GravityEnergy::compute,
-    Momentum::compute,
-    Forces::compute,
+/// The measures are taken right after the physics step has been written back, in order since
+/// the forces need the contacts and accelerations. Everything read during the next frame then
+/// comes from the same step.
+pub fn add_systems(app: &mut App) {
+    app.add_systems(
+        PostUpdate,
+        (
+            KineticEnergy::compute,
+            GravityEnergy::compute,
+            Momentum::compute,
+            Contacts::compute,
+            Acceleration::compute,
+            apply_deferred,
+            Forces::compute,
+        )
+            .chain()
+            .after(PhysicsSet::Writeback)
+            .after(TransformSystem::TransformPropagate),
+    );
+}
+
+/// Simulated time, advanced by each physics step
+#[derive(Resource, Default)]
+pub struct SimulationTime {
+    /// s
+    pub time: f32,
+    pub steps: u64,
+}
+
+impl SimulationTime {
+    pub(crate) fn advance(
+        mut sim: ResMut<SimulationTime>,
+        rapier: Res<RapierContext>,
+        rapier_conf: Res<RapierConfiguration>,
+    ) {
+        if rapier_conf.physics_pipeline_active {
+            sim.time += frame_step(&rapier, &rapier_conf);
+            sim.steps += 1;
+        }
+    }
+}
+
+/// Simulated time covered by the physics steps of a frame, s. The integration parameters only
+/// hold the duration of one substep.
+pub(crate) fn frame_step(rapier: &RapierContext, rapier_conf: &RapierConfiguration) -> f32 {
+    let substeps = match rapier_conf.timestep_mode {
+        TimestepMode::Fixed { substeps, .. }
+        | TimestepMode::Variable { substeps, .. }
+        | TimestepMode::Interpolated { substeps, .. } => substeps,
+    };
+    rapier.integration_parameters.dt * substeps as f32
 }
 
 #[derive(Component)]
@@ -72,11 +123,117 @@ impl Momentum {
     }
 }
 
+#[derive(Copy, Clone, Debug)]
+pub struct Contact {
+    pub other: Entity,
+    /// World space
+    pub point: Vec2,
+    /// Unit normal, pointing from the other body towards this one
+    pub normal: Vec2,
+    /// N, along `normal`
+    pub normal_force: f32,
+    /// N, along `normal.perp()`
+    pub tangent_force: f32,
+}
+
+impl Contact {
+    pub fn normal_force(&self) -> Vec2 {
+        self.normal * self.normal_force
+    }
+
+    pub fn tangent_force(&self) -> Vec2 {
+        self.normal.perp() * self.tangent_force
+    }
+
+    pub fn force(&self) -> Vec2 {
+        self.normal_force() + self.tangent_force()
+    }
+}
+
+/// Contact points of a body during the last physics step, and the forces exerted there
+#[derive(Component, Default)]
+pub struct Contacts {
+    pub contacts: Vec<Contact>,
+}
+
+impl Contacts {
+    pub(crate) fn compute(
+        bodies: Query<Entity, With<ReadMassProperties>>,
+        colliders: Query<&GlobalTransform>,
+        rapier: Res<RapierContext>,
+        mut commands: Commands,
+    ) {
+        let dt = rapier.integration_parameters.dt;
+        for id in bodies.iter() {
+            let mut contacts = vec![];
+            for pair in rapier.contacts_with(id) {
+                if !pair.has_any_active_contacts() {
+                    continue;
+                }
+                let (other, sign) = if pair.collider1() == id {
+                    (pair.collider2(), 1.0)
+                } else {
+                    (pair.collider1(), -1.0)
+                };
+                // colliders can be children of the body, so their frame is the global one; the
+                // collider shape already includes the scale
+                let Ok(xform1) = colliders.get(pair.collider1()) else { continue };
+                let (_, rotation1, translation1) = xform1.to_scale_rotation_translation();
+                for manifold in pair.manifolds() {
+                    // the solver pushes the first collider along -normal
+                    let normal = -manifold.normal() * sign;
+                    for point in manifold.points() {
+                        if point.dist() > 0.0 && point.impulse() == 0.0 {
+                            continue;
+                        }
+                        let local = point.local_p1().extend(0.0);
+                        contacts.push(Contact {
+                            other,
+                            point: translation1.xy() + (rotation1 * local).xy(),
+                            normal,
+                            normal_force: point.impulse() / dt,
+                            tangent_force: point.tangent_impulse() / dt,
+                        });
+                    }
+                }
+            }
+            commands.entity(id).insert(Contacts { contacts });
+        }
+    }
+
+    pub fn total_force(&self) -> Vec2 {
+        self.contacts.iter().map(Contact::force).sum()
+    }
+
+    /// Number of distinct bodies touching this one
+    pub fn count(&self) -> usize {
+        let mut others: Vec<_> = self.contacts.iter().map(|c| c.other).collect();
+        others.sort();
+        others.dedup();
+        others.len()
+    }
+}
+
+#[derive(IntoStaticStr, Copy, Clone, Debug, PartialEq, Eq)]
 pub enum ForceKind {
     Gravity,
     Torque,
+    /// Normal reaction of a contact
+    Contact,
+    Friction,
+    /// Reaction of a hinge or a fixed joint
+    Joint,
+    Motor,
+    /// Mouse drag, thrusters...
+    Custom,
+    /// Linear and angular damping
+    Drag,
+    /// Part of the body's acceleration that the other forces don't account for, where a
+    /// reaction can't be measured (joints in reduced coordinates don't expose their impulses)
+    Unexplained,
 }
 
+#[derive(Copy, Clone, Debug)]
 pub enum ForceValue {
     Force(Vec2),
     Torque(f32),
@@ -94,39 +251,291 @@ impl From<f32> for ForceValue {
     }
 }
 
+#[derive(Copy, Clone, Debug)]
 pub struct AppliedForce {
     pub kind: ForceKind,
+    /// Application point, in the body's local space
     pub at: Vec2,
+    /// World space
     pub value: ForceValue,
 }
 
 #[derive(Component)]
 pub struct Forces {
     forces: Vec<AppliedForce>,
+    /// Local center of mass, torques are computed around it
+    center: Vec2,
+    rotation: Quat,
 }
 
 impl Forces {
-    fn new() -> Self {
-        Self { forces: Vec::new() }
+    pub fn iter(&self) -> impl Iterator<Item = &AppliedForce> {
+        self.forces.iter()
+    }
+
+    /// Forces that were actually measured, estimated residuals excluded
+    fn measured(&self) -> impl Iterator<Item = &AppliedForce> {
+        self.forces.iter().filter(|f| f.kind != ForceKind::Unexplained)
+    }
+
+    pub fn net_force(&self) -> Vec2 {
+        self.measured()
+            .filter_map(|f| match f.value {
+                ForceValue::Force(force) => Some(force),
+                ForceValue::Torque(_) => None,
+            })
+            .sum()
+    }
+
+    /// Around the center of mass
+    pub fn net_torque(&self) -> f32 {
+        self.measured()
+            .map(|f| match f.value {
+                ForceValue::Force(force) => self.lever(f.at).perp_dot(force),
+                ForceValue::Torque(torque) => torque,
+            })
+            .sum()
+    }
+
+    /// World space vector from the center of mass to a local point
+    fn lever(&self, at: Vec2) -> Vec2 {
+        (self.rotation * (at - self.center).extend(0.0)).xy()
     }
 
     pub(crate) fn compute(
-        bodies: Query<(Entity, &ReadMassProperties, &Velocity)>,
+        bodies: Query<(
+            Entity,
+            &ReadMassProperties,
+            &Velocity,
+            &Transform,
+            Option<&Contacts>,
+            Option<&Acceleration>,
+            Option<&GravityScale>,
+            Option<&Damping>,
+            Option<&Children>,
+        )>,
+        impulse_joints: Query<(Entity, &ImpulseJoint)>,
+        multibody_joints: Query<(Entity, &MultibodyJoint)>,
+        custom_forces: Query<&CustomForce, Without<CustomForceDespawn>>,
         mut commands: Commands,
+        rapier: Res<RapierContext>,
         rapier_conf: Res<RapierConfiguration>,
     ) {
         use ForceKind::*;
 
-        for (id, ReadMassProperties(mass), _vel) in bodies.iter() {
-            let mut forces = vec![];
+        let dt = rapier.integration_parameters.dt;
+        let rotation = |ent: Entity| bodies.get(ent).map_or(Quat::IDENTITY, |b| b.3.rotation);
+
+        let mut joint_forces: HashMap<Entity, Vec<AppliedForce>> = HashMap::new();
+        for (child, joint) in impulse_joints.iter() {
+            let Some(raw) = rapier
+                .entity2impulse_joint()
+                .get(&child)
+                .and_then(|&handle| rapier.impulse_joints.get(handle))
+            else {
+                continue;
+            };
+            // the solver applies the impulses, expressed in the first joint frame, to the
+            // parent, and their opposite to the child
+            let frame = rotation(joint.parent)
+                * Quat::from_rotation_z(raw.data.local_frame1.rotation.angle());
+            let linear = (frame * Vec3::new(raw.impulses.x, raw.impulses.y, 0.0)).xy() / dt;
+            let angular = raw.impulses.z / dt;
+            // a free rotation only has a torque when it's driven, otherwise it comes from limits
+            let angular_kind = if !raw.data.locked_axes.contains(JointAxesMask::ANG_X)
+                && raw.data.motor_axes.contains(JointAxesMask::ANG_X)
+            {
+                Motor
+            } else {
+                Joint
+            };
+            for (ent, at, sign) in [
+                (joint.parent, joint.data.local_anchor1(), 1.0),
+                (child, joint.data.local_anchor2(), -1.0),
+            ] {
+                let list = joint_forces.entry(ent).or_default();
+                list.push(AppliedForce {
+                    kind: Joint,
+                    at,
+                    value: (linear * sign).into(),
+                });
+                if angular != 0.0 {
+                    list.push(AppliedForce {
+                        kind: angular_kind,
+                        at,
+                        value: (angular * sign).into(),
+                    });
+                }
+            }
+        }
+
+        // reduced coordinates joints don't expose their impulses, so all that can be shown is
+        // what the measured forces fail to explain of the body's acceleration
+        let mut multibody_anchors: HashMap<Entity, Vec<Vec2>> = HashMap::new();
+        for (child, joint) in multibody_joints.iter() {
+            multibody_anchors
+                .entry(joint.parent)
+                .or_default()
+                .push(joint.data.local_anchor1());
+            multibody_anchors
+                .entry(child)
+                .or_default()
+                .push(joint.data.local_anchor2());
+        }
+
+        for (id, ReadMassProperties(mass), vel, xform, contacts, acc, gravity_scale, damping, children) in
+            bodies.iter()
+        {
+            let mut forces = Forces {
+                forces: vec![],
+                center: mass.local_center_of_mass,
+                rotation: xform.rotation,
+            };
+            let center = mass.local_center_of_mass;
 
-            forces.push(AppliedForce {
+            let gravity_scale = gravity_scale.map_or(1.0, |s| s.0);
+            forces.forces.push(AppliedForce {
                 kind: Gravity,
-                at: Vec2::ZERO,
-                value: Vec2::new(0.0, mass.mass * rapier_conf.gravity.y).into(),
+                at: center,
+                value: (rapier_conf.gravity * mass.mass * gravity_scale).into(),
             });
 
-            commands.entity(id).insert(Forces { forces });
+            for contact in contacts.iter().flat_map(|c| c.contacts.iter()) {
+                let at = xform.to_local(contact.point);
+                forces.forces.push(AppliedForce {
+                    kind: Contact,
+                    at,
+                    value: contact.normal_force().into(),
+                });
+                if contact.tangent_force != 0.0 {
+                    forces.forces.push(AppliedForce {
+                        kind: Friction,
+                        at,
+                        value: contact.tangent_force().into(),
+                    });
+                }
+            }
+
+            forces
+                .forces
+                .extend(joint_forces.remove(&id).into_iter().flatten());
+
+            let attached = children.into_iter().flat_map(|kids| kids.iter());
+            for custom in custom_forces.iter_many(attached) {
+                let ExternalForce { force, torque } = custom.0;
+                if force == Vec2::ZERO {
+                    if torque != 0.0 {
+                        forces.forces.push(AppliedForce {
+                            kind: Custom,
+                            at: center,
+                            value: torque.into(),
+                        });
+                    }
+                    continue;
+                }
+                // point on the line of action closest to the center of mass
+                let lever = force.perp() * -torque / force.length_squared();
+                forces.forces.push(AppliedForce {
+                    kind: Custom,
+                    at: center + (xform.rotation.conjugate() * lever.extend(0.0)).xy(),
+                    value: force.into(),
+                });
+            }
+
+            if let Some(damping) = damping {
+                // rapier scales velocities by 1 / (1 + dt * damping) each step
+                let linear = -vel.linvel * mass.mass * damping.linear_damping
+                    / (1.0 + dt * damping.linear_damping);
+                let angular = -vel.angvel * mass.principal_inertia * damping.angular_damping
+                    / (1.0 + dt * damping.angular_damping);
+                if linear != Vec2::ZERO {
+                    forces.forces.push(AppliedForce {
+                        kind: Drag,
+                        at: center,
+                        value: linear.into(),
+                    });
+                }
+                if angular != 0.0 {
+                    forces.forces.push(AppliedForce {
+                        kind: Drag,
+                        at: center,
+                        value: angular.into(),
+                    });
+                }
+            }
+
+            // the residual isn't part of the net force, which would otherwise always match the
+            // acceleration
+            if let (Some(anchors), Some(acc)) = (multibody_anchors.get(&id), acc) {
+                let at = match anchors.as_slice() {
+                    [anchor] => *anchor,
+                    _ => center,
+                };
+                let residual = acc.linear * mass.mass - forces.net_force();
+                // the residual force, drawn at the joint, already accounts for part of the torque
+                let residual_torque = acc.angular * mass.principal_inertia
+                    - forces.net_torque()
+                    - forces.lever(at).perp_dot(residual);
+                if residual != Vec2::ZERO {
+                    forces.forces.push(AppliedForce {
+                        kind: Unexplained,
+                        at,
+                        value: residual.into(),
+                    });
+                }
+                if residual_torque != 0.0 {
+                    forces.forces.push(AppliedForce {
+                        kind: Unexplained,
+                        at,
+                        value: residual_torque.into(),
+                    });
+                }
+            }
+
+            commands.entity(id).insert(forces);
+        }
+    }
+}
+
+/// Accelerations over the last physics step, obtained by differencing velocities
+#[derive(Component)]
+pub struct Acceleration {
+    pub linear: Vec2,
+    pub angular: f32,
+    prev: Velocity,
+}
+
+impl Acceleration {
+    pub(crate) fn compute(
+        mut bodies: Query<(Entity, &Velocity, Option<&mut Acceleration>)>,
+        rapier: Res<RapierContext>,
+        rapier_conf: Res<RapierConfiguration>,
+        mut commands: Commands,
+    ) {
+        // velocities are sampled once per frame, whatever the number of substeps
+        let dt = frame_step(&rapier, &rapier_conf);
+        for (id, vel, acc) in bodies.iter_mut() {
+            match acc {
+                // without a physics step, velocities only change when edited (scripts, windows,
+                // dragging), which mustn't show up as an acceleration on the next step
+                Some(mut acc) if !rapier_conf.physics_pipeline_active => {
+                    if acc.prev != *vel {
+                        acc.prev = *vel;
+                    }
+                }
+                Some(mut acc) => {
+                    acc.linear = (vel.linvel - acc.prev.linvel) / dt;
+                    acc.angular = (vel.angvel - acc.prev.angvel) / dt;
+                    acc.prev = *vel;
+                }
+                None => {
+                    commands.entity(id).insert(Acceleration {
+                        linear: Vec2::ZERO,
+                        angular: 0.0,
+                        prev: *vel,
+                    });
+                }
+            }
         }
     }
 }
@@ -9,6 +9,7 @@ use bevy_rapier2d::prelude::{ExternalForce, ImpulseJoint};
 use pan::PanState;
 
 use crate::mouse::r#move::MouseLongOrMoved;
+use crate::objects::laser::Emitter;
 use crate::mouse::select::SelectUnderMouseEvent;
 use crate::tools::add_object::{AddHingeEvent, AddObjectEvent};
 use crate::tools::pan;
@@ -126,7 +127,7 @@ pub fn left_release(
                     add_obj.send(AddObjectEvent::Hinge(AddHingeEvent::Mouse(pos)));
                 }
                 Laser(()) => {
-                    add_obj.send(AddObjectEvent::Laser(pos));
+                    add_obj.send(AddObjectEvent::Laser(pos, Emitter::Pen));
                 }
                 Tracer(()) => {
                     todo!()
@@ -0,0 +1,189 @@
+use bevy::math::{Vec2, Vec3Swizzles};
+use bevy::prelude::*;
+use bevy_egui::EguiContexts;
+use bevy_rapier2d::prelude::{ExternalForce, ExternalImpulse};
+use strum::{EnumIter, IntoStaticStr};
+
+use crate::objects::MotorComponent;
+use crate::update_from::UpdateFrom;
+use crate::{systems, CustomForce};
+
+systems!(process_controllers);
+
+#[derive(EnumIter, IntoStaticStr, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ControllerAction {
+    #[strum(serialize = "Motors forward")]
+    MotorForward,
+    #[strum(serialize = "Motors reverse")]
+    MotorReverse,
+    #[strum(serialize = "Thrust")]
+    Thrust,
+    #[strum(serialize = "Jump")]
+    Jump,
+    #[strum(serialize = "Turn left")]
+    TorqueLeft,
+    #[strum(serialize = "Turn right")]
+    TorqueRight,
+}
+
+/// Key bindings for driving an object. Motors are those of the axles attached to the object.
+#[derive(Component, Clone, Debug)]
+pub struct ControllerComponent {
+    pub motor_forward: Option<KeyCode>,
+    pub motor_reverse: Option<KeyCode>,
+    pub thrust: Option<KeyCode>,
+    pub jump: Option<KeyCode>,
+    pub torque_left: Option<KeyCode>,
+    pub torque_right: Option<KeyCode>,
+    /// N
+    pub thrust_force: f32,
+    /// rad, relative to the object
+    pub thrust_angle: f32,
+    /// Ns
+    pub jump_impulse: f32,
+    /// Nm
+    pub torque: f32,
+    force_entity: Option<Entity>,
+    /// Enabled and reversed settings of the motors taken over while a motor key is held,
+    /// restored on release
+    saved_motors: Vec<(Entity, bool, bool)>,
+}
+
+impl Default for ControllerComponent {
+    fn default() -> Self {
+        Self {
+            motor_forward: None,
+            motor_reverse: None,
+            thrust: None,
+            jump: None,
+            torque_left: None,
+            torque_right: None,
+            thrust_force: 10.0,
+            thrust_angle: 0.0,
+            jump_impulse: 5.0,
+            torque: 10.0,
+            force_entity: None,
+            saved_motors: Vec::new(),
+        }
+    }
+}
+
+impl ControllerComponent {
+    pub fn binding(&self, action: ControllerAction) -> Option<KeyCode> {
+        match action {
+            ControllerAction::MotorForward => self.motor_forward,
+            ControllerAction::MotorReverse => self.motor_reverse,
+            ControllerAction::Thrust => self.thrust,
+            ControllerAction::Jump => self.jump,
+            ControllerAction::TorqueLeft => self.torque_left,
+            ControllerAction::TorqueRight => self.torque_right,
+        }
+    }
+
+    pub fn binding_mut(&mut self, action: ControllerAction) -> &mut Option<KeyCode> {
+        match action {
+            ControllerAction::MotorForward => &mut self.motor_forward,
+            ControllerAction::MotorReverse => &mut self.motor_reverse,
+            ControllerAction::Thrust => &mut self.thrust,
+            ControllerAction::Jump => &mut self.jump,
+            ControllerAction::TorqueLeft => &mut self.torque_left,
+            ControllerAction::TorqueRight => &mut self.torque_right,
+        }
+    }
+}
+
+pub fn process_controllers(
+    keys: Res<Input<KeyCode>>,
+    mut egui_ctx: EguiContexts,
+    mut controllers: Query<(
+        Entity,
+        &mut ControllerComponent,
+        &Transform,
+        Option<&Children>,
+        Option<&UpdateFrom<MotorComponent>>,
+        Option<&mut ExternalImpulse>,
+    )>,
+    mut motors: Query<&mut MotorComponent>,
+    mut forces: Query<&mut CustomForce>,
+    mut commands: Commands,
+) {
+    // don't drive objects while typing in a text field
+    let typing = egui_ctx.ctx_mut().wants_keyboard_input();
+    let pressed = |key: Option<KeyCode>| !typing && key.map_or(false, |key| keys.pressed(key));
+
+    for (id, mut controller, xform, children, motor_source, impulse) in controllers.iter_mut() {
+        let forward = pressed(controller.motor_forward);
+        let reverse = pressed(controller.motor_reverse);
+        if forward != reverse {
+            let own_motor = match motor_source {
+                Some(&UpdateFrom::Entity(motor, _)) => Some(motor),
+                _ => None,
+            };
+            let attached = children
+                .into_iter()
+                .flat_map(|kids| kids.iter())
+                .copied()
+                .chain(own_motor);
+            for motor_ent in attached {
+                let Ok(mut motor) = motors.get_mut(motor_ent) else { continue };
+                if !controller.saved_motors.iter().any(|&(ent, ..)| ent == motor_ent) {
+                    controller
+                        .saved_motors
+                        .push((motor_ent, motor.enabled, motor.reversed));
+                }
+                if !motor.enabled || motor.reversed != reverse {
+                    motor.enabled = true;
+                    motor.reversed = reverse;
+                }
+            }
+        } else if !controller.saved_motors.is_empty() {
+            // give the motors back the settings the user chose
+            for (motor_ent, enabled, reversed) in controller.saved_motors.drain(..) {
+                if let Ok(mut motor) = motors.get_mut(motor_ent) {
+                    motor.enabled = enabled;
+                    motor.reversed = reversed;
+                }
+            }
+        }
+
+        let mut force = ExternalForce::default();
+        let center = xform.translation.xy();
+        if pressed(controller.thrust) {
+            let dir = xform.rotation * Vec2::from_angle(controller.thrust_angle).extend(0.0);
+            force += ExternalForce::at_point(dir.xy() * controller.thrust_force, center, center);
+        }
+        if pressed(controller.torque_left) {
+            force.torque += controller.torque;
+        }
+        if pressed(controller.torque_right) {
+            force.torque -= controller.torque;
+        }
+
+        match controller.force_entity.and_then(|ent| forces.get_mut(ent).ok()) {
+            Some(mut custom) => {
+                if custom.0 != force {
+                    custom.0 = force;
+                }
+            }
+            None if force != ExternalForce::default() => {
+                let ent = commands.spawn(CustomForce(force)).set_parent(id).id();
+                controller.force_entity = Some(ent);
+            }
+            None => {}
+        }
+
+        if !typing && controller.jump.map_or(false, |key| keys.just_pressed(key)) {
+            let jump = Vec2::Y * controller.jump_impulse;
+            // don't overwrite an impulse queued by something else this frame
+            match impulse {
+                Some(mut impulse) => impulse.impulse += jump,
+                None => {
+                    commands.entity(id).insert(ExternalImpulse {
+                        impulse: jump,
+                        torque_impulse: 0.0,
+                    });
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use bevy::math::{Vec2, Vec3Swizzles};
+use bevy::prelude::*;
+use bevy_prototype_lyon::prelude::Path;
+use bevy_rapier2d::prelude::*;
+use geo::{BooleanOps, Coord, LineString, MultiPolygon, Polygon};
+use lyon_path::math::point;
+use num_traits::FloatConst;
+
+use crate::objects::hinge::HingeObject;
+use crate::objects::phy_obj::RefractiveIndex;
+use crate::objects::{ColorComponent, MotorComponent};
+use crate::update_from::UpdateFrom;
+use crate::{systems, InvTransformPoint, ToRot};
+
+systems!(process_combine_shapes, event CombineShapesEvent);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CsgOperation {
+    /// Merge the overlapping bodies into the selected one
+    Union,
+    /// Cut the overlapping bodies out of the selected one
+    Subtract,
+    /// Keep only the part of the selected body covered by the overlapping ones
+    Intersect,
+}
+
+#[derive(Copy, Clone, Debug, Event)]
+pub struct CombineShapesEvent {
+    pub entity: Entity,
+    pub operation: CsgOperation,
+}
+
+const CIRCLE_SEGMENTS: usize = 48;
+
+fn empty() -> MultiPolygon<f64> {
+    MultiPolygon::new(vec![])
+}
+
+fn ring(points: impl IntoIterator<Item = Vec2>) -> MultiPolygon<f64> {
+    let coords: Vec<_> = points
+        .into_iter()
+        .map(|p| Coord {
+            x: p.x as f64,
+            y: p.y as f64,
+        })
+        .collect();
+    if coords.len() < 3 {
+        return empty();
+    }
+    MultiPolygon::new(vec![Polygon::new(LineString::new(coords), vec![])])
+}
+
+/// Converts a collider into polygons, with each vertex mapped through `to_target`. Circles are
+/// approximated by regular polygons.
+fn shape_polygons(shape: ColliderView, to_target: &dyn Fn(Vec2) -> Vec2) -> MultiPolygon<f64> {
+    match shape {
+        ColliderView::Ball(ball) => ring((0..CIRCLE_SEGMENTS).map(|i| {
+            let angle = i as f32 * f32::TAU() / CIRCLE_SEGMENTS as f32;
+            to_target(Vec2::from_angle(angle) * ball.radius())
+        })),
+        ColliderView::Cuboid(cuboid) => {
+            let half = cuboid.half_extents();
+            ring(
+                [
+                    Vec2::new(-half.x, -half.y),
+                    Vec2::new(half.x, -half.y),
+                    half,
+                    Vec2::new(-half.x, half.y),
+                ]
+                .map(to_target),
+            )
+        }
+        ColliderView::Triangle(tri) => ring([tri.a(), tri.b(), tri.c()].map(to_target)),
+        ColliderView::ConvexPolygon(poly) => ring(poly.points().map(to_target)),
+        ColliderView::Compound(compound) => {
+            compound
+                .shapes()
+                .fold(empty(), |acc, (pos, rot, shape)| {
+                    let to_part = |p: Vec2| to_target(pos + Vec2::from_angle(rot).rotate(p));
+                    acc.union(&shape_polygons(shape, &to_part))
+                })
+        }
+        _ => empty(),
+    }
+}
+
+/// Outer boundaries and holes of the polygons, without the closing vertex.
+fn rings(polygons: &MultiPolygon<f64>) -> impl Iterator<Item = Vec<Vec2>> + '_ {
+    polygons
+        .iter()
+        .flat_map(|poly| std::iter::once(poly.exterior()).chain(poly.interiors().iter()))
+        .map(|line| {
+            let mut points: Vec<_> = line
+                .coords()
+                .map(|c| Vec2::new(c.x as f32, c.y as f32))
+                .collect();
+            if line.is_closed() {
+                points.pop();
+            }
+            points
+        })
+}
+
+fn polygons_to_collider(polygons: &MultiPolygon<f64>) -> Option<Collider> {
+    let mut vertices = vec![];
+    let mut indices = vec![];
+    for ring in rings(polygons) {
+        let base = vertices.len() as u32;
+        let count = ring.len() as u32;
+        vertices.extend(ring);
+        indices.extend((0..count).map(|i| [base + i, base + (i + 1) % count]));
+    }
+    if vertices.is_empty() {
+        return None;
+    }
+    Some(Collider::convex_decomposition(&vertices, &indices))
+}
+
+fn polygons_to_path(polygons: &MultiPolygon<f64>) -> Path {
+    let mut builder = lyon_path::Path::builder();
+    for ring in rings(polygons) {
+        let mut points = ring.into_iter();
+        let Some(first) = points.next() else { continue };
+        builder.begin(point(first.x, first.y));
+        for p in points {
+            builder.line_to(point(p.x, p.y));
+        }
+        builder.close();
+    }
+    Path(builder.build())
+}
+
+pub fn process_combine_shapes(
+    mut events: EventReader<CombineShapesEvent>,
+    rapier: Res<RapierContext>,
+    bodies: Query<(&Transform, &Collider, &ColliderMassProperties), With<RefractiveIndex>>,
+    xforms: Query<&Transform>,
+    children: Query<&Children>,
+    mut impulse_joints: Query<(Entity, &mut ImpulseJoint)>,
+    mut multibody_joints: Query<(Entity, &mut MultibodyJoint)>,
+    hinges: Query<(), With<HingeObject>>,
+    motor_sources: Query<&UpdateFrom<MotorComponent>>,
+    mut color_sources: Query<&mut UpdateFrom<ColorComponent>>,
+    mut commands: Commands,
+) {
+    for &CombineShapesEvent { entity, operation } in events.iter() {
+        let Ok((xform, collider, mass_props)) = bodies.get(entity) else { continue };
+
+        let mut others = vec![];
+        rapier.intersections_with_shape(
+            xform.translation.xy(),
+            xform.rotation.to_rot(),
+            collider,
+            QueryFilter::new()
+                .exclude_sensors()
+                .exclude_collider(entity),
+            |other| {
+                if bodies.contains(other) {
+                    others.push(other);
+                }
+                true
+            },
+        );
+
+        if others.is_empty() {
+            info!("Combine shapes: nothing overlaps {:?}", entity);
+            continue;
+        }
+
+        // everything is computed in the local frame of the selected body
+        let target = shape_polygons(collider.as_typed_shape(), &|p| p);
+        let others_shape = others.iter().fold(empty(), |acc, &other| {
+            let (other_xform, other_collider, _) = bodies.get(other).unwrap();
+            acc.union(&shape_polygons(other_collider.as_typed_shape(), &|p| {
+                xform.to_local(other_xform.to_global(p))
+            }))
+        });
+
+        let result = match operation {
+            CsgOperation::Union => target.union(&others_shape),
+            CsgOperation::Subtract => target.difference(&others_shape),
+            CsgOperation::Intersect => target.intersection(&others_shape),
+        };
+
+        let Some(new_collider) = polygons_to_collider(&result) else {
+            info!("Combine shapes: {:?} of {:?} is empty", operation, entity);
+            continue;
+        };
+
+        info!("Combine shapes: {:?} of {:?} with {:?}", operation, entity, others);
+
+        // reinserting the mass properties makes Rapier recompute them for the new shape
+        commands
+            .entity(entity)
+            .insert((new_collider, polygons_to_path(&result), *mass_props));
+
+        if operation == CsgOperation::Subtract {
+            continue;
+        }
+
+        for &other in &others {
+            let other_xform = xforms.get(other).unwrap();
+            let to_result = |p: Vec2| xform.to_local(other_xform.to_global(p));
+
+            macro_rules! reattach {
+                ($joints:ident, $ty:ident) => {
+                    let mut moved = None;
+                    for (joint_ent, mut joint) in $joints.iter_mut() {
+                        if joint_ent == other {
+                            // the consumed body was the child side of the joint
+                            if joint.parent != entity && !others.contains(&joint.parent) {
+                                let mut data = joint.data;
+                                data.set_local_anchor2(to_result(data.local_anchor2()));
+                                moved = Some($ty::new(joint.parent, data));
+                            }
+                        } else if joint.parent == other {
+                            if joint_ent == entity || others.contains(&joint_ent) {
+                                commands.entity(joint_ent).remove::<$ty>();
+                            } else {
+                                joint.parent = entity;
+                                let anchor = to_result(joint.data.local_anchor1());
+                                joint.data.set_local_anchor1(anchor);
+                            }
+                        }
+                    }
+                    if let Some(joint) = moved {
+                        if $joints.contains(entity) {
+                            info!("Combine shapes: {:?} already has a {}, dropping the one of {:?}", entity, stringify!($ty), other);
+                        } else {
+                            let mut cmds = commands.entity(entity);
+                            cmds.insert(joint);
+                            if hinges.contains(other) {
+                                cmds.insert((HingeObject, ActiveHooks::FILTER_CONTACT_PAIRS));
+                            }
+                            if let Ok(&UpdateFrom::Entity(motor, _)) = motor_sources.get(other) {
+                                cmds.insert(UpdateFrom::<MotorComponent>::entity(motor));
+                            }
+                        }
+                    }
+                };
+            }
+
+            reattach!(impulse_joints, ImpulseJoint);
+            reattach!(multibody_joints, MultibodyJoint);
+
+            if let Ok(kids) = children.get(other) {
+                for &child in kids.iter() {
+                    // windows don't have a transform and go away with the body
+                    let Ok(child_xform) = xforms.get(child) else { continue };
+                    let matrix = xform.compute_matrix().inverse()
+                        * other_xform.compute_matrix()
+                        * child_xform.compute_matrix();
+                    commands
+                        .entity(child)
+                        .set_parent(entity)
+                        .insert(Transform::from_matrix(matrix));
+                }
+            }
+
+            for mut source in color_sources.iter_mut() {
+                if matches!(*source, UpdateFrom::Entity(e, _) if e == other) {
+                    *source = UpdateFrom::entity(entity);
+                }
+            }
+
+            commands.entity(other).despawn_recursive();
+        }
+    }
+}
@@ -1,21 +1,234 @@
 use std::fmt::{Debug, Formatter};
+use std::ops::RangeInclusive;
 
-use bevy::hierarchy::{BuildChildren, DespawnRecursiveExt};
+use bevy::utils::{HashMap, HashSet};
+
+use bevy::hierarchy::BuildChildren;
 use bevy::math::{EulerRot, Vec2, Vec3, Vec3Swizzles};
 use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::sprite::Mesh2dHandle;
+use bevy::tasks::ComputeTaskPool;
 use bevy_egui::egui::ecolor::Hsva;
-use bevy_prototype_lyon::geometry::GeometryBuilder;
-use bevy_prototype_lyon::prelude::ShapeBundle;
-use bevy_prototype_lyon::shapes;
-use bevy_rapier2d::prelude::{QueryFilter, RapierContext, RayIntersection};
+use bevy_egui::{egui, EguiContexts};
+use bevy_mouse_tracking_plugin::{MainCamera, MousePosWorld};
+use bevy_rapier2d::prelude::{
+    Collider, ExternalForce, QueryFilter, RapierContext, RayIntersection, ReadMassProperties,
+};
+use itertools::Itertools;
 use num_traits::float::FloatConst;
+use strum::{EnumIter, IntoStaticStr};
 
-use crate::objects::phy_obj::RefractiveIndex;
+use crate::config::AppConfig;
+use crate::{CustomForce, CustomForceDespawn, InvTransformPoint, FOREGROUND_Z};
+use crate::measures::SimulationTime;
+use crate::objects::lens::Lens;
+use crate::objects::phy_obj::{OpticalMaterial, RefractiveIndex, REFERENCE_WAVELENGTH};
 use crate::objects::ColorComponent;
 
 #[derive(Component)]
 pub struct LaserBundle {
     pub(crate) fade_distance: f32,
+    pub(crate) spectrum: Spectrum,
+    /// Width of the beam at the output, m
+    pub(crate) width: f32,
+    /// Full angle of the beam's spread, rad
+    pub(crate) divergence: f32,
+    /// Output power, W
+    pub(crate) power: f32,
+    pub(crate) enabled: bool,
+    pub(crate) pulse: Option<Pulse>,
+    /// Push the objects hit by the rays
+    pub(crate) radiation_pressure: bool,
+    /// Multiplier of the radiation pressure, so that it can be seen
+    pub(crate) pressure_factor: f32,
+    pub(crate) emitter: Emitter,
+    /// Maximum number of rays traced, including the reflected and refracted ones, split evenly
+    /// between the rays of the emitter
+    pub(crate) max_rays: usize,
+}
+
+impl LaserBundle {
+    pub fn new(spectrum: Spectrum, width: f32) -> Self {
+        Self {
+            fade_distance: 10.0,
+            spectrum,
+            width,
+            divergence: 0.0,
+            power: 0.005,
+            enabled: true,
+            pulse: None,
+            radiation_pressure: false,
+            pressure_factor: 1e9,
+            emitter: Emitter::Pen,
+            max_rays: MAX_RAYS,
+        }
+    }
+
+    pub fn with_emitter(self, emitter: Emitter) -> Self {
+        Self { emitter, ..self }
+    }
+
+    /// Power of each ray at full strength, W. The rays of an emitter are drawn as bright as a
+    /// single beam, but share its power.
+    pub fn ray_power(&self) -> f32 {
+        self.power / self.emitter.ray_count() as f32
+    }
+
+    /// Whether the laser emits at a given simulation time.
+    pub fn is_on(&self, time: f32) -> bool {
+        self.enabled && self.pulse.map_or(true, |pulse| pulse.is_on(time))
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Pulse {
+    /// s
+    pub period: f32,
+    /// Fraction of the period during which the laser emits
+    pub duty_cycle: f32,
+}
+
+impl Default for Pulse {
+    fn default() -> Self {
+        Self {
+            period: 1.0,
+            duty_cycle: 0.5,
+        }
+    }
+}
+
+impl Pulse {
+    fn is_on(&self, time: f32) -> bool {
+        (time / self.period).fract() < self.duty_cycle
+    }
+}
+
+/// How the light leaves the laser, the power being shared evenly between the rays
+#[derive(IntoStaticStr, Copy, Clone, Debug, PartialEq)]
+pub enum Emitter {
+    /// A single beam
+    #[strum(serialize = "Laser pen")]
+    Pen,
+    /// Rays spread evenly over an angle (rad)
+    #[strum(serialize = "Point light")]
+    Point { rays: usize, angle: f32 },
+    /// Parallel rays spread evenly over a width (m)
+    #[strum(serialize = "Parallel beam")]
+    Parallel { rays: usize, width: f32 },
+}
+
+impl Emitter {
+    pub const POINT_LIGHT: Emitter = Emitter::Point {
+        rays: 32,
+        angle: std::f32::consts::FRAC_PI_2,
+    };
+    pub const PARALLEL_BEAM: Emitter = Emitter::Parallel {
+        rays: 16,
+        width: 1.0,
+    };
+    pub const KINDS: [Emitter; 3] = [Emitter::Pen, Emitter::POINT_LIGHT, Emitter::PARALLEL_BEAM];
+
+    pub fn ray_count(&self) -> usize {
+        match *self {
+            Emitter::Pen => 1,
+            Emitter::Point { rays, .. } | Emitter::Parallel { rays, .. } => rays.max(1),
+        }
+    }
+
+    /// Offset across the beam (m), angle from the axis (rad), width and spread of each ray
+    fn rays(&self, laser: &LaserBundle) -> Vec<(f32, f32, f32, f32)> {
+        let spread = 2.0 * (laser.divergence / 2.0).tan();
+        let count = self.ray_count();
+        // from -0.5 to 0.5, in the middle of each ray's share
+        let fractions = (0..count).map(move |i| (i as f32 + 0.5) / count as f32 - 0.5);
+        match *self {
+            Emitter::Pen => vec![(0.0, 0.0, laser.width, spread)],
+            Emitter::Point { angle, .. } => {
+                // each ray covers its share of the angle
+                let wedge = 2.0 * (angle / count as f32 / 2.0).min(1.4).tan();
+                fractions.map(|f| (0.0, f * angle, 0.0, wedge)).collect()
+            }
+            Emitter::Parallel { width, .. } => fractions
+                .map(|f| (f * width, 0.0, width / count as f32, spread))
+                .collect(),
+        }
+    }
+}
+
+/// Wavelengths perceived by the eye, nm
+pub const VISIBLE_RANGE: RangeInclusive<f32> = 380.0..=750.0;
+
+/// Number of wavelengths white light is split into by dispersive materials
+const SPECTRUM_SAMPLES: usize = 12;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Spectrum {
+    /// Single wavelength, nm
+    Monochromatic(f32),
+    /// Evenly spread over the visible range
+    White,
+}
+
+impl Spectrum {
+    /// Closest spectral color to a hue, white light if the color is too pale.
+    pub fn from_color(color: Hsva) -> Self {
+        if color.s < 0.5 {
+            return Spectrum::White;
+        }
+        // red (0) to violet (0.75), purples are closer to red
+        let hue = if color.h > 0.875 { 0.0 } else { color.h.min(0.75) };
+        Spectrum::Monochromatic(700.0 - 300.0 * hue / 0.75)
+    }
+
+    pub fn wavelength(&self) -> Option<f32> {
+        match *self {
+            Spectrum::Monochromatic(wavelength) => Some(wavelength),
+            Spectrum::White => None,
+        }
+    }
+
+    pub fn color(&self) -> Hsva {
+        match *self {
+            Spectrum::Monochromatic(wavelength) => wavelength_to_color(wavelength),
+            Spectrum::White => Hsva::new(0.0, 0.0, 1.0, 1.0),
+        }
+    }
+
+    /// Wavelengths white light is sampled at, nm
+    fn samples() -> impl Iterator<Item = f32> {
+        (0..SPECTRUM_SAMPLES).map(|i| 400.0 + 300.0 * (i as f32 + 0.5) / SPECTRUM_SAMPLES as f32)
+    }
+}
+
+/// Approximate color of a wavelength in nm (Dan Bruton's piecewise fit), black outside of the
+/// visible range.
+pub fn wavelength_to_color(wavelength: f32) -> Hsva {
+    let w = wavelength;
+    let [r, g, b] = if !VISIBLE_RANGE.contains(&w) {
+        [0.0, 0.0, 0.0]
+    } else if w < 440.0 {
+        [(440.0 - w) / (440.0 - 380.0), 0.0, 1.0]
+    } else if w < 490.0 {
+        [0.0, (w - 440.0) / (490.0 - 440.0), 1.0]
+    } else if w < 510.0 {
+        [0.0, 1.0, (510.0 - w) / (510.0 - 490.0)]
+    } else if w < 580.0 {
+        [(w - 510.0) / (580.0 - 510.0), 1.0, 0.0]
+    } else if w < 645.0 {
+        [1.0, (645.0 - w) / (645.0 - 580.0), 0.0]
+    } else {
+        [1.0, 0.0, 0.0]
+    };
+    // the eye is less sensitive near the edges
+    let factor = if w < 420.0 {
+        0.3 + 0.7 * (w - 380.0) / (420.0 - 380.0)
+    } else if w > 700.0 {
+        0.3 + 0.7 * (750.0 - w) / (750.0 - 700.0)
+    } else {
+        1.0
+    };
+    Hsva::from_rgb([r * factor, g * factor, b * factor])
 }
 
 struct LaserRay {
@@ -23,30 +236,41 @@ struct LaserRay {
     angle: f32,
     length: f32,
     strength: f32,
-    color: Hsva,
+    light: Spectrum,
+    /// Width at the start of the ray
     width: f32,
+    /// Width gained per meter
+    spread: f32,
     start_distance: f32,
-    refractive_index: f32,
+    /// Medium the ray travels through
+    refractive_index: RefractiveIndex,
+    /// Beer–Lambert attenuation coefficient of the medium, 1/m
+    absorption: f32,
+    /// Object the ray travels through
+    inside: Option<Entity>,
     kind: RayKind,
     num: usize,
     source: usize,
     start_angle: f32,
     end_angle: f32,
+    /// Object the ray ends on
+    hit: Option<Entity>,
 }
 
-#[derive(Debug)]
+#[derive(IntoStaticStr, Debug)]
 enum RayKind {
     Laser,
     Reflected,
     Refracted,
+    Diffused,
 }
 
 impl Debug for LaserRay {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{:2} ({:2}): {{ {:.3}, {:.1}°, L={:.1}m, {:.1}%, w: {:.1}m, n: {:?}, {:?}, S: {:.1}°, E: {:.1}° }}",
-            self.num, self.source, self.start, self.angle.to_degrees(), self.length, self.strength * 100.0, self.width, self.refractive_index, self.kind,
+            "{:2} ({:2}): {{ {:.3}, {:.1}°, L={:.1}m, {:.1}%, {:?}, w: {:.1}m, n: {:.3}, {:?}, S: {:.1}°, E: {:.1}° }}",
+            self.num, self.source, self.start, self.angle.to_degrees(), self.length, self.strength * 100.0, self.light, self.width, self.refractive_index.nominal(), self.kind,
             self.start_angle.to_degrees(), self.end_angle.to_degrees()
         )
     }
@@ -68,45 +292,132 @@ impl LaserRay {
     }
 
     fn end_strength(&self, parent: &LaserBundle) -> f32 {
-        0.0f32
-            .max(self.strength * (1.0 - self.length / (parent.fade_distance - self.start_distance)))
+        let absorbed = (-self.absorption * self.length).exp();
+        0.0f32.max(
+            self.strength
+                * absorbed
+                * (1.0 - self.length / (parent.fade_distance - self.start_distance)),
+        )
     }
 
     fn end_distance(&self) -> f32 {
         self.start_distance + self.length
     }
 
+    fn end_width(&self) -> f32 {
+        self.width + self.spread * self.length_clipped()
+    }
+
+    /// Area covered by the ray, including its width
+    fn bounds(&self) -> Rect {
+        let half_width = Vec2::splat(self.width.max(self.end_width()) / 2.0);
+        let mut bounds = Rect::from_corners(self.start, self.end());
+        bounds.min -= half_width;
+        bounds.max += half_width;
+        bounds
+    }
+
+    /// Normal of the surface hit, facing the ray
+    fn end_normal(&self) -> Option<Vec2> {
+        self.hit
+            .map(|_| Vec2::from_angle(self.angle + f32::PI() - self.end_angle))
+    }
+
+    /// Index of the medium for the light of the ray
+    fn index(&self) -> f32 {
+        self.refractive_index
+            .at(self.light.wavelength().unwrap_or(REFERENCE_WAVELENGTH))
+    }
+
     fn color_blended(&self) -> Hsva {
-        Hsva::new(self.color.h, self.color.s, self.color.v, self.strength)
+        let color = self.light.color();
+        Hsva::new(color.h, color.s, color.v, self.strength)
     }
 }
 
 struct ObjectInfo {
-    refractive_index: f32,
+    refractive_index: RefractiveIndex,
+    material: OpticalMaterial,
     color: Hsva,
+    /// Analytic shape, whose normals are used instead of those of the collider
+    lens: Option<(Lens, Transform)>,
+}
+
+/// How the intensity is split between the reflected and refracted rays
+#[derive(EnumIter, IntoStaticStr, Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum FresnelModel {
+    /// Exact equations for unpolarized light
+    #[default]
+    Fresnel,
+    /// Schlick's approximation
+    Schlick,
+}
+
+impl FresnelModel {
+    /// Fraction of the intensity reflected when going from `index_in` to `index_out`, the angles
+    /// being measured from the normal.
+    fn reflectance(self, angle_in: f32, angle_out: f32, index_in: f32, index_out: f32) -> f32 {
+        let (cos_in, cos_out) = (angle_in.cos().abs(), angle_out.cos().abs());
+        match self {
+            FresnelModel::Fresnel => {
+                let s = (index_in * cos_in - index_out * cos_out)
+                    / (index_in * cos_in + index_out * cos_out);
+                let p = (index_in * cos_out - index_out * cos_in)
+                    / (index_in * cos_out + index_out * cos_in);
+                (s * s + p * p) / 2.0
+            }
+            FresnelModel::Schlick => {
+                let r0 = ((index_in - index_out) / (index_in + index_out)).powi(2);
+                // the angle on the side of the less dense medium
+                let cos = if index_in <= index_out { cos_in } else { cos_out };
+                r0 + (1.0 - r0) * (1.0 - cos).powi(5)
+            }
+        }
+        .clamp(0.0, 1.0)
+    }
 }
 
 struct LaserCompute<'a, ObjInfo: Fn(Entity) -> ObjectInfo> {
     laser: &'a LaserBundle,
     rapier: &'a RapierContext,
     object_info: ObjInfo,
+    fresnel: FresnelModel,
     rays: Vec<LaserRay>,
+    /// Momentum given to the objects hit, relative to the output of the laser
+    transfers: Vec<MomentumTransfer>,
+    /// Ray count at which the tracing of the current starting ray stops
+    ray_limit: usize,
 }
 
+struct MomentumTransfer {
+    entity: Entity,
+    point: Vec2,
+    momentum: Vec2,
+}
+
+/// Default cap on the number of rays traced for a laser
 const MAX_RAYS: usize = 1000;
 
 impl<'a, ObjInfo: Fn(Entity) -> ObjectInfo> LaserCompute<'a, ObjInfo> {
-    fn new(laser: &'a LaserBundle, rapier: &'a RapierContext, object_info: ObjInfo) -> Self {
+    fn new(
+        laser: &'a LaserBundle,
+        rapier: &'a RapierContext,
+        object_info: ObjInfo,
+        fresnel: FresnelModel,
+    ) -> Self {
         Self {
             laser,
             rapier,
             object_info,
+            fresnel,
             rays: Vec::new(),
+            transfers: Vec::new(),
+            ray_limit: laser.max_rays,
         }
     }
 
     fn shoot_ray(&mut self, mut ray: LaserRay, ray_count: &mut usize) {
-        if *ray_count > MAX_RAYS {
+        if *ray_count >= self.ray_limit {
             return;
         }
 
@@ -151,7 +462,19 @@ impl<'a, ObjInfo: Fn(Entity) -> ObjectInfo> LaserCompute<'a, ObjInfo> {
         )) = intersection
         {
             ray.length = toi;
+            ray.hit = Some(ent);
 
+            let ObjectInfo {
+                refractive_index: obj_index,
+                material,
+                color: obj_color,
+                lens,
+            } = (self.object_info)(ent);
+
+            let normal = match lens {
+                Some((lens, xform)) => lens.world_normal(&xform, point),
+                None => normal,
+            };
             let normal = if normal.dot(ray_dir) > 0.0 {
                 -normal
             } else {
@@ -160,15 +483,7 @@ impl<'a, ObjInfo: Fn(Entity) -> ObjectInfo> LaserCompute<'a, ObjInfo> {
 
             let normal_angle = normal.y.atan2(normal.x);
 
-            let mut inside_object = false;
-            self.rapier.intersections_with_point(
-                ray.start + (toi / 2.0) * ray_dir,
-                QueryFilter::default().predicate(&|scrutinee| scrutinee == ent),
-                |_| {
-                    inside_object = true;
-                    false
-                },
-            );
+            let inside_object = ray.inside == Some(ent);
 
             let incidence_angle = (f32::PI() + ray.angle) - normal_angle;
 
@@ -176,33 +491,15 @@ impl<'a, ObjInfo: Fn(Entity) -> ObjectInfo> LaserCompute<'a, ObjInfo> {
 
             ray.end_angle = incidence_angle;
 
-            let ObjectInfo {
-                refractive_index: obj_index,
-                color: obj_color,
-            } = (self.object_info)(ent);
-
-            let opacity_refracted = (-obj_index.log10()).exp();
-            let opacity_reflected = 1.0 - opacity_refracted;
-            let reflected_ray = LaserRay {
-                start: point,
-                angle: reflected_angle,
-                length: f32::INFINITY,
-                strength: ray.end_strength(self.laser) * opacity_reflected,
-                color: ray.color_blended(),
-                width: ray.width,
-                start_distance: ray.end_distance(),
-                refractive_index: ray.refractive_index,
-                kind: RayKind::Reflected,
-                num: *ray_count,
-                source: ray.num,
-                start_angle: -incidence_angle,
-                end_angle: 0.0,
-            };
-
-            self.shoot_ray(reflected_ray, ray_count);
+            let incoming = ray.end_strength(self.laser);
+            // incoming minus outgoing momentum, what is absorbed is kept by the object
+            let mut transfer = incoming * ray_dir;
+            let mirrored = incoming * material.reflectivity;
+            // whatever is not transmitted is reflected, so that no intensity is created
+            let mut reflected_strength = incoming;
 
-            if f32::is_finite(obj_index) {
-                let obj_index = if inside_object {
+            if !obj_index.is_opaque() {
+                let (obj_index, absorption, medium) = if inside_object {
                     let mut object_other = None;
                     self.rapier.intersections_with_point(
                         point,
@@ -215,73 +512,152 @@ impl<'a, ObjInfo: Fn(Entity) -> ObjectInfo> LaserCompute<'a, ObjInfo> {
                         },
                     );
                     match object_other {
-                        Some(ent) => (self.object_info)(ent).refractive_index,
-                        None => 1.0,
+                        Some(other_ent) => {
+                            let other = (self.object_info)(other_ent);
+                            (other.refractive_index, other.material.absorption, object_other)
+                        }
+                        None => (RefractiveIndex::VACUUM, 0.0, None),
                     }
                 } else {
-                    obj_index
+                    (obj_index, material.absorption, Some(ent))
                 };
 
-                // todo: make sure total color strength is bounded by the incident ray
-                let strength = ray.end_strength(self.laser) * opacity_refracted;
-
-                let alpha_inv = 1.0 - obj_color.a;
-                let color_strength = |_hue| alpha_inv;
-
-                let rainbow_strength = strength * (1.0 - ray.color.s) * RAINBOW_SPLIT_MULT;
-                let refraction_strength = strength * ray.color.s;
+                // the filter is applied once, when entering the object
+                let transmission = |wavelength| {
+                    if inside_object {
+                        1.0
+                    } else {
+                        material.transmission(wavelength, obj_color)
+                    }
+                };
 
-                let mut shoot_refracted_ray = |color: Hsva, base_strength| {
-                    let ref_index = adjust_index(obj_index, color.h);
-                    if let Some(ref_angle) =
-                        compute_new_angle(incidence_angle, ray.refractive_index, obj_index)
+                let components: Vec<(Spectrum, f32)> = match ray.light {
+                    Spectrum::White
+                        if obj_index.is_dispersive()
+                            || ray.refractive_index.is_dispersive()
+                            || (!inside_object && material.is_tinted()) =>
                     {
-                        let refracted_ray = LaserRay {
-                            start: point,
-                            angle: (normal_angle - f32::PI()) + ref_angle,
-                            length: f32::INFINITY,
-                            strength: base_strength * color_strength(color.h),
-                            color: color,
-                            width: refraction_thickness(ray.width, incidence_angle, ref_angle),
-                            start_distance: ray.end_distance(),
-                            refractive_index: ref_index,
-                            kind: RayKind::Refracted,
-                            num: *ray_count,
-                            source: ray.num,
-                            start_angle: ref_angle,
-                            end_angle: 0.0,
-                        };
-
-                        self.shoot_ray(refracted_ray, ray_count);
+                        Spectrum::samples()
+                            .map(|wavelength| (Spectrum::Monochromatic(wavelength), wavelength))
+                            .collect()
                     }
+                    Spectrum::White => vec![(Spectrum::White, REFERENCE_WAVELENGTH)],
+                    Spectrum::Monochromatic(wavelength) => vec![(ray.light, wavelength)],
                 };
+                let base_strength = (incoming - mirrored) / components.len() as f32;
 
-                if refraction_strength > 0.0 {
-                    shoot_refracted_ray(ray.color, refraction_strength);
+                for (light, wavelength) in components {
+                    let index_in = ray.refractive_index.at(wavelength);
+                    let index_out = obj_index.at(wavelength);
+                    // total internal reflection otherwise
+                    let Some(ref_angle) =
+                        compute_new_angle(incidence_angle, index_in, index_out) else { continue };
+                    let reflectance =
+                        self.fresnel.reflectance(incidence_angle, ref_angle, index_in, index_out);
+                    let transmitted = base_strength * (1.0 - reflectance);
+                    reflected_strength -= transmitted;
+
+                    let refracted_ray = LaserRay {
+                        start: point,
+                        angle: (normal_angle - f32::PI()) + ref_angle,
+                        length: f32::INFINITY,
+                        // the object absorbs the rest
+                        strength: transmitted * transmission(wavelength),
+                        light,
+                        width: refraction_thickness(ray.end_width(), incidence_angle, ref_angle),
+                        spread: refraction_thickness(ray.spread, incidence_angle, ref_angle),
+                        start_distance: ray.end_distance(),
+                        refractive_index: obj_index,
+                        absorption,
+                        inside: medium,
+                        kind: RayKind::Refracted,
+                        num: *ray_count,
+                        source: ray.num,
+                        start_angle: ref_angle,
+                        end_angle: 0.0,
+                        hit: None,
+                    };
+
+                    transfer -= refracted_ray.strength * Vec2::from_angle(refracted_ray.angle);
+                    self.shoot_ray(refracted_ray, ray_count);
                 }
+            }
 
-                if rainbow_strength > 0.0 {
-                    let mut color = Hsva::new(0.0, 1.0, 1.0, 1.0);
+            let reflected_strength = reflected_strength.max(0.0);
+            let diffused = reflected_strength * material.diffuse;
 
-                    for i in 0..COLORS_IN_RAINBOW {
-                        color.h = 0.5 * (2.0 * i as f32 + 1.0) / COLORS_IN_RAINBOW as f32;
-                        shoot_refracted_ray(color, rainbow_strength);
-                    }
+            let reflected_ray = LaserRay {
+                start: point,
+                angle: reflected_angle,
+                length: f32::INFINITY,
+                strength: reflected_strength - diffused,
+                light: ray.light,
+                width: ray.end_width(),
+                spread: ray.spread,
+                start_distance: ray.end_distance(),
+                refractive_index: ray.refractive_index,
+                absorption: ray.absorption,
+                inside: ray.inside,
+                kind: RayKind::Reflected,
+                num: *ray_count,
+                source: ray.num,
+                start_angle: -incidence_angle,
+                end_angle: 0.0,
+                hit: None,
+            };
+
+            transfer -= reflected_ray.strength * Vec2::from_angle(reflected_ray.angle);
+            self.shoot_ray(reflected_ray, ray_count);
+
+            if diffused > 0.0 {
+                // Lambertian scattering, sampled at evenly spaced angles from the normal
+                let angles = (0..DIFFUSE_RAYS)
+                    .map(|i| f32::PI() * ((i as f32 + 0.5) / DIFFUSE_RAYS as f32 - 0.5));
+                let total_weight: f32 = angles.clone().map(f32::cos).sum();
+                for angle in angles {
+                    let diffused_ray = LaserRay {
+                        start: point,
+                        angle: normal_angle + angle,
+                        length: f32::INFINITY,
+                        strength: diffused * angle.cos() / total_weight,
+                        light: ray.light,
+                        width: ray.end_width(),
+                        spread: ray.spread,
+                        start_distance: ray.end_distance(),
+                        refractive_index: ray.refractive_index,
+                        absorption: ray.absorption,
+                        inside: ray.inside,
+                        kind: RayKind::Diffused,
+                        num: *ray_count,
+                        source: ray.num,
+                        start_angle: angle,
+                        end_angle: 0.0,
+                        hit: None,
+                    };
+
+                    transfer -= diffused_ray.strength * Vec2::from_angle(diffused_ray.angle);
+                    self.shoot_ray(diffused_ray, ray_count);
                 }
             }
+
+            self.transfers.push(MomentumTransfer {
+                entity: ent,
+                point,
+                momentum: transfer,
+            });
         }
 
         self.rays.push(ray);
     }
 
-    fn end(self) -> Vec<LaserRay> {
-        self.rays
+    fn end(self) -> (Vec<LaserRay>, Vec<MomentumTransfer>) {
+        (self.rays, self.transfers)
     }
 }
 
 const STRENGTH_EPSILON: f32 = 0.9 / 255.0;
-const RAINBOW_SPLIT_MULT: f32 = 1.0 / 3.0;
-const COLORS_IN_RAINBOW: usize = 12;
+/// Number of rays a diffuse surface scatters the light into
+const DIFFUSE_RAYS: usize = 8;
 
 fn refraction_thickness(thickness: f32, angle_in: f32, angle_out: f32) -> f32 {
     let miter_width = thickness / angle_in.cos();
@@ -289,11 +665,6 @@ fn refraction_thickness(thickness: f32, angle_in: f32, angle_out: f32) -> f32 {
     //thickness * (angle - side_angle).sin() / (side_angle - angle + f32::FRAC_PI_2()).cos()
 }
 
-fn adjust_index(base_index: f32, hue: f32) -> f32 {
-    let hue_360 = hue * 360.0;
-    base_index + (1.206e-4 * (hue_360 - 180.0) * (base_index * base_index))
-}
-
 fn compute_new_angle(incidence: f32, index_ray: f32, index_new: f32) -> Option<f32> {
     let new_sin = incidence.sin() * index_ray / index_new;
     if !(-1.0..=1.0).contains(&new_sin) {
@@ -312,102 +683,534 @@ fn compute_new_angle(incidence: f32, index_ray: f32, index_new: f32) -> Option<f
     }
 }
 
-const LASER_WIDTH: f32 = 0.2;
+/// m/s
+const SPEED_OF_LIGHT: f32 = 299_792_458.0;
 
-pub fn draw_lasers(
-    lasers: Query<(&Transform, &LaserBundle, &ColorComponent)>,
-    refr: Query<(&RefractiveIndex, &ColorComponent), Without<LaserBundle>>,
-    mut rays: Query<(Entity, &mut LaserRays)>,
-    mut commands: Commands,
-    rapier: Res<RapierContext>,
-) {
-    let (rays, mut rays_obj) = rays.single_mut();
-    commands.entity(rays).despawn_descendants();
+/// Marks the force applied by the lasers on an object
+#[derive(Component)]
+pub struct RadiationPressure;
 
-    for (transform, laser, color) in lasers.iter() {
-        let ray_width = transform.scale.x * LASER_WIDTH;
+/// Default beam width, relative to the size of the laser pen
+pub(crate) const LASER_WIDTH: f32 = 0.1;
 
-        let start = transform.transform_point(Vec3::new(0.5, 0.0, 1.0)).xy();
+/// Rays of a laser, as traced the last time something relevant changed
+struct TracedLaser {
+    rays: Vec<LaserRay>,
+    hits: Vec<LaserHitEvent>,
+    transfers: Vec<MomentumTransfer>,
+    /// Whether the laser was emitting
+    on: bool,
+    /// Depth of the laser pen
+    z: f32,
+    /// Objects the rays hit or went through
+    touched: HashSet<Entity>,
+    /// Area covered by all the rays
+    bounds: Rect,
+}
+
+impl TracedLaser {
+    fn off(z: f32) -> Self {
+        Self {
+            rays: vec![],
+            hits: vec![],
+            transfers: vec![],
+            on: false,
+            z,
+            touched: HashSet::new(),
+            bounds: Rect::default(),
+        }
+    }
+
+    /// Whether a change to an object, currently covering `bounds` if it's still there, may
+    /// change the rays.
+    fn affected_by(&self, ent: Entity, bounds: Option<Rect>) -> bool {
+        self.touched.contains(&ent)
+            || bounds.map_or(false, |bounds| {
+                !self.bounds.intersect(bounds).is_empty()
+                    && self.rays.iter().any(|ray| !ray.bounds().intersect(bounds).is_empty())
+            })
+    }
+}
+
+/// Traced lasers, kept as long as the scene doesn't change
+#[derive(Resource, Default)]
+pub struct LaserCache {
+    lasers: HashMap<Entity, TracedLaser>,
+    /// Objects changed during the previous frame
+    settling: Vec<Entity>,
+    /// The rays must be drawn again
+    redraw: bool,
+}
+
+impl LaserCache {
+    /// Forces every laser to be traced again.
+    pub fn invalidate(&mut self) {
+        self.lasers.clear();
+    }
+
+    pub fn ray_count(&self) -> usize {
+        self.lasers.values().map(|traced| traced.rays.len()).sum()
+    }
+}
+
+type ObjectQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static RefractiveIndex,
+        &'static OpticalMaterial,
+        &'static ColorComponent,
+        Option<&'static Lens>,
+        &'static Transform,
+    ),
+    Without<LaserBundle>,
+>;
+
+fn trace_laser(
+    transform: &Transform,
+    laser: &LaserBundle,
+    rapier: &RapierContext,
+    objects: &ObjectQuery,
+    fresnel: FresnelModel,
+) -> TracedLaser {
+    let origin = transform.transform_point(Vec3::new(0.5, 0.0, 1.0)).xy();
+    let axis = transform.rotation.to_euler(EulerRot::XYZ).2;
+
+    let mut compute = LaserCompute::new(
+        laser,
+        rapier,
+        |ent| {
+            let (refr, material, col, lens, xform) = objects.get(ent).unwrap();
+            ObjectInfo {
+                refractive_index: *refr,
+                material: *material,
+                color: col.0,
+                lens: lens.map(|lens| (*lens, *xform)),
+            }
+        },
+        fresnel,
+    );
+
+    // each starting ray gets its share of the budget, so that a busy part of the scene doesn't
+    // starve the rest of the beam, and is always shot
+    let starting_rays = laser.emitter.ray_count();
+    let mut ray_count = 0;
+    for (i, (offset, angle, width, spread)) in laser.emitter.rays(laser).into_iter().enumerate() {
+        let share = laser.max_rays / starting_rays + usize::from(i < laser.max_rays % starting_rays);
+        compute.ray_limit = ray_count + share.max(1);
+        let start = origin + Vec2::from_angle(axis).perp() * offset;
         let mut object_other = None;
         rapier.intersections_with_point(start, QueryFilter::default().exclude_sensors(), |ent| {
             object_other = Some(ent);
             false
         });
-        let start_index = match object_other {
-            Some(ent) => refr.get(ent).unwrap().0 .0,
-            None => 1.0,
+        let (start_index, start_absorption) = match object_other {
+            Some(ent) => {
+                let (refr, material, ..) = objects.get(ent).unwrap();
+                (*refr, material.absorption)
+            }
+            None => (RefractiveIndex::VACUUM, 0.0),
         };
 
         let initial = LaserRay {
             start,
-            angle: transform.rotation.to_euler(EulerRot::XYZ).2,
+            angle: axis + angle,
             length: laser.fade_distance,
             strength: 1.0,
-            color: color.0,
-            width: ray_width / 2.0,
+            light: laser.spectrum,
+            width,
+            spread,
             start_distance: 0.0,
             refractive_index: start_index,
+            absorption: start_absorption,
+            inside: object_other,
             kind: RayKind::Laser,
-            num: 0,
-            source: 0,
+            num: ray_count,
+            source: ray_count,
             start_angle: 0.0,
             end_angle: 0.0,
+            hit: None,
         };
+        compute.shoot_ray(initial, &mut ray_count);
+    }
 
-        let mut compute = LaserCompute::new(laser, &rapier, |ent| {
-            let (refr, col) = refr.get(ent).unwrap();
-            ObjectInfo {
-                refractive_index: refr.0,
-                color: col.0,
+    let (rays, transfers) = compute.end();
+    let hits = rays
+        .iter()
+        .filter_map(|ray| {
+            Some(LaserHitEvent {
+                entity: ray.hit?,
+                strength: ray.end_strength(laser),
+                power: ray.end_strength(laser) * laser.ray_power(),
+                color: ray.light.color(),
+                wavelength: ray.light.wavelength(),
+            })
+        })
+        .collect();
+
+    let touched = rays.iter().flat_map(|ray| [ray.hit, ray.inside]).flatten().collect();
+    let bounds = rays
+        .iter()
+        .map(LaserRay::bounds)
+        .reduce(|a, b| a.union(b))
+        .unwrap_or_default();
+
+    TracedLaser {
+        rays,
+        hits,
+        transfers,
+        on: true,
+        z: transform.translation.z,
+        touched,
+        bounds,
+    }
+}
+
+/// Current bounding box of an object, as known to the physics engine
+fn collider_bounds(rapier: &RapierContext, ent: Entity) -> Option<Rect> {
+    let handle = rapier.entity2collider().get(&ent)?;
+    let aabb = rapier.colliders.get(*handle)?.compute_aabb();
+    let scale = rapier.physics_scale();
+    Some(Rect::new(
+        aabb.mins.x * scale,
+        aabb.mins.y * scale,
+        aabb.maxs.x * scale,
+        aabb.maxs.y * scale,
+    ))
+}
+
+/// Traces the lasers whose rays may have changed, in parallel.
+pub fn trace_lasers(
+    lasers: Query<(Entity, Ref<Transform>, Ref<LaserBundle>)>,
+    objects: ObjectQuery,
+    scene_changes: Query<
+        Entity,
+        (
+            With<Collider>,
+            Without<LaserBundle>,
+            Or<(
+                Changed<Transform>,
+                Changed<Collider>,
+                Changed<RefractiveIndex>,
+                Changed<OpticalMaterial>,
+                Changed<ColorComponent>,
+            )>,
+        ),
+    >,
+    mut removed: RemovedComponents<Collider>,
+    rapier: Res<RapierContext>,
+    config: Res<AppConfig>,
+    sim: Res<SimulationTime>,
+    mut cache: ResMut<LaserCache>,
+) {
+    let changed = scene_changes.iter().chain(removed.iter()).collect_vec();
+    // the physics engine only sees the changes at the end of the frame, so look at them again on
+    // the next one, where they are
+    let settling = std::mem::replace(&mut cache.settling, changed.clone());
+    let changed = changed
+        .into_iter()
+        .chain(settling)
+        .unique()
+        .map(|ent| (ent, collider_bounds(&rapier, ent)))
+        .collect_vec();
+
+    let count = cache.lasers.len();
+    cache.lasers.retain(|&ent, _| lasers.contains(ent));
+    if cache.lasers.len() != count {
+        cache.redraw = true;
+    }
+
+    let jobs = lasers
+        .iter()
+        .filter(|(ent, xform, laser)| match cache.lasers.get(ent) {
+            Some(traced) => {
+                config.is_changed()
+                    || changed.iter().any(|&(obj, bounds)| traced.affected_by(obj, bounds))
+                    || xform.is_changed()
+                    || laser.is_changed()
+                    || traced.on != laser.is_on(sim.time)
             }
-        });
+            None => true,
+        })
+        .map(|(ent, xform, laser)| (ent, *xform, laser.into_inner()))
+        .collect_vec();
+    if jobs.is_empty() {
+        return;
+    }
 
-        let mut ray_count = 0;
-        compute.shoot_ray(initial, &mut ray_count);
+    let (rapier, objects, fresnel) = (&*rapier, &objects, config.fresnel);
+    let traced = ComputeTaskPool::get().scope(|scope| {
+        for (ent, xform, laser) in jobs.iter() {
+            let on = laser.is_on(sim.time);
+            scope.spawn(async move {
+                let traced = if on {
+                    trace_laser(xform, laser, rapier, objects, fresnel)
+                } else {
+                    TracedLaser::off(xform.translation.z)
+                };
+                (*ent, traced)
+            });
+        }
+    });
 
-        let ray_list = compute.end();
+    cache.lasers.extend(traced);
+    cache.redraw = true;
+}
 
-        let mut debug = String::new();
+/// Sends the hits and applies the radiation pressure of the traced lasers, and draws their rays
+/// as a single mesh.
+pub fn draw_lasers(
+    lasers: Query<&LaserBundle>,
+    mut cache: ResMut<LaserCache>,
+    mut rays: Query<(&mut LaserRays, &Mesh2dHandle, &mut Transform, &mut Visibility)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut commands: Commands,
+    bodies: Query<(&Transform, &ReadMassProperties), Without<LaserRays>>,
+    mut pressures: Query<
+        (Entity, &Parent, &mut CustomForce),
+        (With<RadiationPressure>, Without<CustomForceDespawn>),
+    >,
+    mut hits: EventWriter<LaserHitEvent>,
+) {
+    // sensors and forces expect a reading every frame, even when nothing was traced again
+    let mut pressure_forces: HashMap<Entity, ExternalForce> = HashMap::new();
+    for (&laser_ent, traced) in cache.lasers.iter() {
+        hits.send_batch(traced.hits.iter().copied());
 
-        for ray in ray_list {
+        let Ok(laser) = lasers.get(laser_ent) else { continue };
+        if laser.radiation_pressure {
+            // F = P / c for each unit of momentum flux
+            let scale = laser.ray_power() / SPEED_OF_LIGHT * laser.pressure_factor;
+            for transfer in traced.transfers.iter() {
+                let Ok((xform, ReadMassProperties(mass))) = bodies.get(transfer.entity) else { continue };
+                let center = xform.to_global(mass.local_center_of_mass);
+                *pressure_forces.entry(transfer.entity).or_default() +=
+                    ExternalForce::at_point(transfer.momentum * scale, transfer.point, center);
+            }
+        }
+    }
+
+    for (id, parent, mut force) in pressures.iter_mut() {
+        match pressure_forces.remove(&parent.get()) {
+            Some(value) => {
+                if force.0 != value {
+                    force.0 = value;
+                }
+            }
+            None => {
+                commands.entity(id).insert(CustomForceDespawn);
+            }
+        }
+    }
+    for (body, force) in pressure_forces {
+        commands
+            .spawn((CustomForce(force), RadiationPressure))
+            .set_parent(body);
+    }
+
+    if !cache.redraw {
+        return;
+    }
+    cache.redraw = false;
+
+    let Ok((mut rays_obj, mesh, mut rays_xform, mut visibility)) = rays.get_single_mut() else { return };
+
+    let mut positions: Vec<[f32; 3]> = vec![];
+    let mut colors: Vec<[f32; 4]> = vec![];
+    let mut indices: Vec<u32> = vec![];
+    let mut debug = String::new();
+    let mut z = f32::NEG_INFINITY;
+
+    for traced in cache.lasers.values() {
+        z = z.max(traced.z);
+        for ray in traced.rays.iter() {
             debug.push_str(&format!("{:?}\n", ray));
             let start = ray.start;
             let end = ray.end();
-            let thick = ray.width; // todo: lazer_fuzziness
-            let halfthick = thick / 2.0;
+            // todo: lazer_fuzziness
+            let halfthick_start = ray.width / 2.0;
+            let halfthick_end = ray.end_width() / 2.0;
             let dir = (end - start).normalize();
-            let norm = dir.perp() * halfthick;
-            let diff_start = dir * halfthick * ray.start_angle.tan();
-            let diff_end = dir * halfthick * ray.end_angle.tan();
-            let poly = shapes::Polygon {
-                points: vec![
-                    start + norm + diff_start,
-                    start - norm - diff_start,
-                    end - norm - diff_end,
-                    end + norm + diff_end,
-                ],
-                closed: true,
-            };
-            commands
-                .spawn((
-                    ShapeBundle {
-                        path: GeometryBuilder::build_as(&poly),
-                        transform: Transform::from_translation(Vec3::new(
-                            0.0,
-                            0.0,
-                            transform.translation.z - 0.1,
-                        )),
-                        ..Default::default()
-                    },
-                    crate::make_fill(crate::hsva_to_rgba(ray.color_blended())),
-                ))
-                .set_parent(rays);
+            let norm_start = dir.perp() * halfthick_start;
+            let norm_end = dir.perp() * halfthick_end;
+            let diff_start = dir * halfthick_start * ray.start_angle.tan();
+            let diff_end = dir * halfthick_end * ray.end_angle.tan();
+
+            let first = positions.len() as u32;
+            positions.extend(
+                [
+                    start + norm_start + diff_start,
+                    start - norm_start - diff_start,
+                    end - norm_end - diff_end,
+                    end + norm_end + diff_end,
+                ]
+                .map(|p| [p.x, p.y, 0.0]),
+            );
+            let color = crate::hsva_to_rgba(ray.color_blended()).as_linear_rgba_f32();
+            colors.extend([color; 4]);
+            indices.extend([0, 1, 2, 0, 2, 3].map(|i| first + i));
+        }
+    }
+
+    rays_obj.debug = debug;
+    if positions.is_empty() {
+        *visibility = Visibility::Hidden;
+        return;
+    }
+    *visibility = Visibility::Visible;
+    // below the highest laser pen
+    rays_xform.translation.z = z - 0.1;
+
+    let mut new_mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    new_mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    new_mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    new_mesh.set_indices(Some(Indices::U32(indices)));
+    if let Some(mesh) = meshes.get_mut(&mesh.0) {
+        *mesh = new_mesh;
+    }
+}
+
+/// Half length of the normals drawn at the interfaces, m
+const NORMAL_LENGTH: f32 = 0.4;
+/// Radius of the arcs showing the angles of the rays, m
+const ANGLE_ARC_RADIUS: f32 = 0.25;
+const INCIDENCE_COLOR: Color = Color::rgba(1.0, 0.8, 0.2, 0.5);
+const OUTGOING_COLOR: Color = Color::rgba(0.2, 0.8, 1.0, 0.5);
+
+/// Arc of `angle` starting at the direction `from`, both in radians, closed by its radii.
+fn draw_angle_arc(gizmos: &mut Gizmos, center: Vec2, from: f32, angle: f32, color: Color) {
+    let angle = wrap_angle(angle);
+    let steps = ((angle.abs() / f32::TAU() * 32.0).ceil() as usize).max(1);
+    let arc = (0..=steps).map(|i| {
+        let a = from + angle * i as f32 / steps as f32;
+        center + Vec2::from_angle(a) * ANGLE_ARC_RADIUS
+    });
+    gizmos.linestrip(
+        std::iter::once(center)
+            .chain(arc)
+            .chain(std::iter::once(center))
+            .map(|p| p.extend(FOREGROUND_Z)),
+        color,
+    );
+}
+
+/// Draws the normals at the interfaces hit by the rays, and the angles of the rays to them.
+pub fn draw_laser_angles(cache: Res<LaserCache>, config: Res<AppConfig>, mut gizmos: Gizmos) {
+    if !config.laser_angles {
+        return;
+    }
+    for ray in cache.lasers.values().flat_map(|traced| traced.rays.iter()) {
+        if let Some(normal) = ray.end_normal() {
+            let end = ray.end();
+            gizmos.line(
+                (end - normal * NORMAL_LENGTH).extend(FOREGROUND_Z),
+                (end + normal * NORMAL_LENGTH).extend(FOREGROUND_Z),
+                Color::WHITE,
+            );
+            // from the normal to the incoming ray
+            draw_angle_arc(&mut gizmos, end, normal.y.atan2(normal.x), ray.end_angle, INCIDENCE_COLOR);
+        }
+        if matches!(ray.kind, RayKind::Reflected | RayKind::Refracted) {
+            // from the normal to the outgoing ray
+            draw_angle_arc(&mut gizmos, ray.start, ray.angle - ray.start_angle, ray.start_angle, OUTGOING_COLOR);
         }
+    }
+}
 
-        rays_obj.debug = debug;
+/// Angle in ]-π, π]
+fn wrap_angle(angle: f32) -> f32 {
+    let wrapped = (angle + f32::PI()).rem_euclid(f32::TAU()) - f32::PI();
+    if wrapped == -f32::PI() {
+        f32::PI()
+    } else {
+        wrapped
     }
 }
 
+/// Describes the ray under the mouse in a tooltip.
+pub fn inspect_laser_rays(
+    cache: Res<LaserCache>,
+    lasers: Query<&LaserBundle>,
+    mouse: Res<MousePosWorld>,
+    cameras: Query<&Transform, With<MainCamera>>,
+    mut egui_ctx: EguiContexts,
+) {
+    let ctx = egui_ctx.ctx_mut();
+    if ctx.is_pointer_over_area() {
+        return;
+    }
+    let Ok(camera) = cameras.get_single() else { return };
+    let mouse = mouse.xy();
+    // a few pixels around thin rays
+    let margin = 4.0 * camera.scale.x;
+
+    let hovered = cache
+        .lasers
+        .iter()
+        .flat_map(|(&ent, traced)| traced.rays.iter().map(move |ray| (ent, ray)))
+        .filter_map(|(ent, ray)| {
+            let (start, end) = (ray.start, ray.end());
+            let along = (mouse - start).dot(end - start) / (end - start).length_squared();
+            if !(0.0..=1.0).contains(&along) {
+                return None;
+            }
+            let distance = mouse.distance(start.lerp(end, along));
+            let half_width = (ray.width + ray.spread * ray.length_clipped() * along) / 2.0;
+            (distance <= half_width.max(margin)).then_some((distance, ent, ray))
+        })
+        .min_by(|(a, ..), (b, ..)| a.total_cmp(b));
+    let Some((_, laser_ent, ray)) = hovered else { return };
+    let Ok(laser) = lasers.get(laser_ent) else { return };
+
+    egui::show_tooltip_at_pointer(ctx, egui::Id::new("laser_ray_inspector"), |ui| {
+        let kind: &'static str = ray.kind.into();
+        ui.strong(match ray.kind {
+            RayKind::Laser => format!("{} ray #{}", kind, ray.num),
+            _ => format!("{} ray #{} (from #{})", kind, ray.num, ray.source),
+        });
+        match ray.kind {
+            RayKind::Reflected => {
+                ui.label(format!("Reflection angle: {:.1}°", wrap_angle(ray.start_angle).abs().to_degrees()));
+            }
+            RayKind::Refracted => {
+                ui.label(format!("Refraction angle: {:.1}°", wrap_angle(ray.start_angle).abs().to_degrees()));
+            }
+            RayKind::Diffused => {
+                ui.label(format!("Diffusion angle: {:.1}°", wrap_angle(ray.start_angle).abs().to_degrees()));
+            }
+            RayKind::Laser => {}
+        }
+        if ray.hit.is_some() {
+            ui.label(format!("Incidence angle: {:.1}°", wrap_angle(ray.end_angle).abs().to_degrees()));
+        }
+        let end_strength = ray.end_strength(laser);
+        ui.label(format!(
+            "Strength: {:.1}% → {:.1}%",
+            ray.strength * 100.0,
+            end_strength * 100.0
+        ));
+        ui.label(format!("Power: {:.3e} W", end_strength * laser.ray_power()));
+        ui.label(format!("Refractive index: {:.4}", ray.index()));
+        if let Some(wavelength) = ray.light.wavelength() {
+            ui.label(format!("Wavelength: {:.0} nm", wavelength));
+        }
+        ui.label(format!("Length: {:.3} m", ray.length_clipped()));
+    });
+}
+
+/// Sent every frame for each ray ending on an object
+#[derive(Event, Copy, Clone, Debug)]
+pub struct LaserHitEvent {
+    pub entity: Entity,
+    /// Relative to the output of the laser
+    pub strength: f32,
+    /// W
+    pub power: f32,
+    pub color: Hsva,
+    /// nm, `None` for white light
+    pub wavelength: Option<f32>,
+}
+
 #[derive(Component, Default)]
 pub struct LaserRays {
     pub debug: String,
@@ -0,0 +1,284 @@
+use bevy::math::{Vec2, Vec3};
+use bevy::prelude::*;
+use bevy_prototype_lyon::entity::{Path, ShapeBundle};
+use bevy_prototype_lyon::geometry::GeometryBuilder;
+use bevy_prototype_lyon::shapes;
+use bevy_egui::egui::ecolor::Hsva;
+use bevy_rapier2d::geometry::Collider;
+use strum::{EnumIter, IntoStaticStr};
+
+use crate::objects::phy_obj::{OpticalMaterial, PhysicalObject, RefractiveIndex, REFERENCE_WAVELENGTH};
+use crate::{systems, InvTransformPoint, FOREGROUND_Z};
+
+systems!(update_lens_shapes, draw_focal_points);
+
+/// Number of segments approximating each curved surface in the collider and the drawing
+const SURFACE_SEGMENTS: usize = 32;
+
+/// Thickness kept at the edge of the lens when the surfaces would cross, m
+const MIN_EDGE: f32 = 0.01;
+
+#[derive(EnumIter, IntoStaticStr, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LensKind {
+    Biconvex,
+    Biconcave,
+    #[strum(serialize = "Plano-convex")]
+    PlanoConvex,
+    #[strum(serialize = "Plano-concave")]
+    PlanoConcave,
+    #[strum(serialize = "Concave mirror")]
+    ConcaveMirror,
+    #[strum(serialize = "Convex mirror")]
+    ConvexMirror,
+}
+
+impl LensKind {
+    pub fn is_mirror(self) -> bool {
+        matches!(self, LensKind::ConcaveMirror | LensKind::ConvexMirror)
+    }
+
+    /// Clear glass, or a perfect mirror, whatever their appearance.
+    pub fn material(self) -> OpticalMaterial {
+        OpticalMaterial {
+            reflectivity: if self.is_mirror() { 1.0 } else { 0.0 },
+            filter: Some(Hsva::new(0.0, 0.0, 1.0, 0.0)),
+            ..Default::default()
+        }
+    }
+
+    /// Color the object is drawn with
+    pub fn color(self) -> Hsva {
+        if self.is_mirror() {
+            Hsva::new(0.0, 0.0, 0.8, 1.0)
+        } else {
+            Hsva::new(0.55, 0.3, 1.0, 0.3)
+        }
+    }
+
+    /// Whether parallel rays converge after going through it.
+    fn is_converging(self) -> bool {
+        matches!(
+            self,
+            LensKind::Biconvex | LensKind::PlanoConvex | LensKind::ConcaveMirror
+        )
+    }
+}
+
+/// Object bounded on the left and on the right by circular arcs, whose normals are computed
+/// exactly when a laser hits it. Its optical axis is its local X axis.
+#[derive(Component, Copy, Clone, Debug, PartialEq)]
+pub struct Lens {
+    pub kind: LensKind,
+    /// Radius of curvature of the left surface, m, positive when its center is on the right,
+    /// infinite when flat
+    pub front: f32,
+    /// Radius of curvature of the right surface, m, same convention
+    pub back: f32,
+    /// Thickness on the axis, m
+    pub thickness: f32,
+    /// Height, m
+    pub aperture: f32,
+    pub show_focal_points: bool,
+}
+
+impl Lens {
+    pub fn new(kind: LensKind, focal_length: f32, index: f32) -> Self {
+        let mut lens = Self {
+            kind,
+            front: f32::INFINITY,
+            back: f32::INFINITY,
+            thickness: if kind.is_mirror() { 0.05 } else { 0.1 },
+            aperture: 1.0,
+            show_focal_points: true,
+        };
+        lens.set_focal_length(focal_length, index);
+        lens
+    }
+
+    /// Sets the radii giving a focal length (its sign is that of the kind of lens), for a
+    /// material of the given index.
+    pub fn set_focal_length(&mut self, focal_length: f32, index: f32) {
+        let f = if self.kind.is_converging() {
+            focal_length.abs()
+        } else {
+            -focal_length.abs()
+        };
+        let (n, d) = (index, self.thickness);
+        let (front, back) = match self.kind {
+            LensKind::Biconvex | LensKind::Biconcave => {
+                // lensmaker's equation with R₂ = -R₁, solved for R₁
+                let r = f * (n - 1.0) * (1.0 + (1.0 - d / (n * f)).max(0.0).sqrt());
+                (r, -r)
+            }
+            LensKind::PlanoConvex | LensKind::PlanoConcave => (f * (n - 1.0), f32::INFINITY),
+            // concave towards the left, where the light comes from
+            LensKind::ConcaveMirror | LensKind::ConvexMirror => (-2.0 * f, -2.0 * f),
+        };
+        self.front = self.clamp_radius(front);
+        self.back = self.clamp_radius(back);
+    }
+
+    /// Focal length for a material of the given index, m, negative when diverging.
+    pub fn focal_length(&self, index: f32) -> f32 {
+        if self.kind.is_mirror() {
+            return -self.front / 2.0;
+        }
+        let (n, d) = (index, self.thickness);
+        // lensmaker's equation for thick lenses
+        let power = (n - 1.0)
+            * (1.0 / self.front - 1.0 / self.back + (n - 1.0) * d / (n * self.front * self.back));
+        1.0 / power
+    }
+
+    /// A surface can't be more curved than a half circle spanning the aperture.
+    pub fn clamp_radius(&self, radius: f32) -> f32 {
+        radius.signum() * radius.abs().max(self.aperture / 2.0)
+    }
+
+    /// X coordinates of the left and right surfaces on the axis, the surfaces being moved apart if
+    /// they would cross at the edge.
+    fn vertices(&self) -> (f32, f32) {
+        let h = self.aperture / 2.0;
+        let overlap = sag(self.front, h) - sag(self.back, h);
+        let thickness = self.thickness.max(overlap + MIN_EDGE);
+        (-thickness / 2.0, thickness / 2.0)
+    }
+
+    /// Outline of the object in its local frame, counterclockwise.
+    pub fn outline(&self) -> Vec<Vec2> {
+        let h = self.aperture / 2.0;
+        let (left, right) = self.vertices();
+        let steps = (0..=SURFACE_SEGMENTS).map(|i| h * (2.0 * i as f32 / SURFACE_SEGMENTS as f32 - 1.0));
+        // left surface from top to bottom, then right surface from bottom to top
+        steps
+            .clone()
+            .rev()
+            .map(|y| Vec2::new(left + sag(self.front, y), y))
+            .chain(steps.map(|y| Vec2::new(right + sag(self.back, y), y)))
+            .collect()
+    }
+
+    pub fn collider(&self) -> Collider {
+        let vertices = self.outline();
+        let count = vertices.len() as u32;
+        let indices = (0..count).map(|i| [i, (i + 1) % count]).collect::<Vec<_>>();
+        // concave lenses are not convex
+        Collider::convex_decomposition(&vertices, &indices)
+    }
+
+    pub fn path(&self) -> Path {
+        GeometryBuilder::build_as(&shapes::Polygon {
+            points: self.outline(),
+            closed: true,
+        })
+    }
+
+    pub fn physical_object(&self, pos: Vec3) -> PhysicalObject {
+        PhysicalObject::make(
+            self.collider(),
+            ShapeBundle {
+                path: self.path(),
+                transform: Transform::from_translation(pos),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Normal of the closest surface to a point in the local frame, in either direction.
+    fn normal(&self, point: Vec2) -> Vec2 {
+        let (left, right) = self.vertices();
+        let surface = |vertex: f32, radius: f32| {
+            if radius.is_finite() {
+                let center = Vec2::new(vertex + radius, 0.0);
+                let offset = point - center;
+                ((offset.length() - radius.abs()).abs(), offset.normalize_or_zero())
+            } else {
+                ((point.x - vertex).abs(), Vec2::X)
+            }
+        };
+        let edge = (
+            self.aperture / 2.0 - point.y.abs(),
+            Vec2::Y * point.y.signum(),
+        );
+        [surface(left, self.front), surface(right, self.back), edge]
+            .into_iter()
+            .min_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|(_, normal)| normal)
+            .unwrap()
+    }
+
+    /// Exact normal of the surface at a point of the scene, in either direction.
+    pub fn world_normal(&self, xform: &Transform, point: Vec2) -> Vec2 {
+        let local = self.normal(xform.to_local(point));
+        // normals are scaled by the inverse of the scale
+        let scaled = local / xform.scale.truncate();
+        (xform.rotation * scaled.extend(0.0)).truncate().normalize_or_zero()
+    }
+
+    /// Focal points in the local frame (thin lens approximation), a single one for mirrors.
+    pub fn focal_points(&self, index: f32) -> Vec<Vec2> {
+        let f = self.focal_length(index);
+        if !f.is_finite() {
+            return vec![];
+        }
+        if self.kind.is_mirror() {
+            let (left, _) = self.vertices();
+            vec![Vec2::new(left - f, 0.0)]
+        } else {
+            vec![Vec2::new(-f, 0.0), Vec2::new(f, 0.0)]
+        }
+    }
+}
+
+/// Offset along the axis of a surface of the given radius, at a height `y`.
+fn sag(radius: f32, y: f32) -> f32 {
+    if !radius.is_finite() {
+        return 0.0;
+    }
+    radius - radius.signum() * (radius * radius - y * y).max(0.0).sqrt()
+}
+
+pub fn update_lens_shapes(
+    mut lenses: Query<(Entity, &Lens, &mut Path), Changed<Lens>>,
+    mut commands: Commands,
+) {
+    for (id, lens, mut path) in lenses.iter_mut() {
+        *path = lens.path();
+        commands.entity(id).insert(lens.collider());
+    }
+}
+
+/// Half size of the crosses marking the focal points, m
+const FOCAL_POINT_SIZE: f32 = 0.05;
+
+pub fn draw_focal_points(
+    lenses: Query<(&Lens, &Transform, &RefractiveIndex)>,
+    mut gizmos: Gizmos,
+) {
+    let z = FOREGROUND_Z;
+    for (lens, xform, index) in lenses.iter() {
+        if !lens.show_focal_points {
+            continue;
+        }
+        let points = lens.focal_points(index.at(REFERENCE_WAVELENGTH));
+        let Some(farthest) = points.iter().map(|p| p.x.abs()).reduce(f32::max) else { continue };
+
+        // optical axis, up to the focal points
+        let reach = farthest.max(lens.thickness) + 2.0 * FOCAL_POINT_SIZE;
+        gizmos.line(
+            xform.to_global(Vec2::new(-reach, 0.0)).extend(z),
+            xform.to_global(Vec2::new(reach, 0.0)).extend(z),
+            Color::rgba(1.0, 1.0, 1.0, 0.3),
+        );
+        for point in points {
+            for diagonal in [Vec2::ONE, Vec2::new(1.0, -1.0)] {
+                let offset = diagonal * FOCAL_POINT_SIZE;
+                gizmos.line(
+                    xform.to_global(point - offset).extend(z),
+                    xform.to_global(point + offset).extend(z),
+                    Color::YELLOW,
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_egui::egui::ecolor::Hsva;
+
+use crate::objects::laser::LaserHitEvent;
+use crate::objects::MotorComponent;
+use crate::systems;
+use crate::update_from::UpdateFrom;
+
+systems!(update_light_sensors, event LightSensorEvent);
+
+/// Measures the laser light falling on an object
+#[derive(Component, Copy, Clone, Debug)]
+pub struct LightSensor {
+    /// Power above which the sensor is lit, W
+    pub threshold: f32,
+    /// Run the motors of the axles attached to the object while lit
+    pub drive_motors: bool,
+    /// Total power received during the last frame, W
+    pub power: f32,
+    /// Average color of the light received
+    pub color: Hsva,
+    /// Average wavelength of the monochromatic light received, nm
+    pub wavelength: Option<f32>,
+    pub lit: bool,
+}
+
+impl Default for LightSensor {
+    fn default() -> Self {
+        Self {
+            threshold: 0.001,
+            drive_motors: false,
+            power: 0.0,
+            color: Hsva::new(0.0, 0.0, 0.0, 1.0),
+            wavelength: None,
+            lit: false,
+        }
+    }
+}
+
+/// Sent when a sensor becomes lit or dark
+#[derive(Event, Copy, Clone, Debug)]
+pub struct LightSensorEvent {
+    pub entity: Entity,
+    pub lit: bool,
+    pub power: f32,
+}
+
+#[derive(Default)]
+struct Reading {
+    power: f32,
+    rgb: [f32; 3],
+    wavelength: f32,
+    monochromatic: f32,
+}
+
+pub fn update_light_sensors(
+    mut hits: EventReader<LaserHitEvent>,
+    mut sensors: Query<(
+        Entity,
+        &mut LightSensor,
+        Option<&Children>,
+        Option<&UpdateFrom<MotorComponent>>,
+    )>,
+    mut motors: Query<&mut MotorComponent>,
+    mut events: EventWriter<LightSensorEvent>,
+) {
+    let mut readings: HashMap<Entity, Reading> = HashMap::new();
+    for hit in hits.iter() {
+        if !sensors.contains(hit.entity) {
+            continue;
+        }
+        let reading = readings.entry(hit.entity).or_default();
+        reading.power += hit.power;
+        for (sum, channel) in reading.rgb.iter_mut().zip(hit.color.to_rgb()) {
+            *sum += channel * hit.power;
+        }
+        if let Some(wavelength) = hit.wavelength {
+            reading.wavelength += wavelength * hit.power;
+            reading.monochromatic += hit.power;
+        }
+    }
+
+    for (id, mut sensor, children, motor_source) in sensors.iter_mut() {
+        let reading = readings.remove(&id).unwrap_or_default();
+        if sensor.power == 0.0 && reading.power == 0.0 && !sensor.lit {
+            // don't trigger change detection for sensors left in the dark
+            continue;
+        }
+        sensor.power = reading.power;
+        sensor.color = if reading.power > 0.0 {
+            Hsva::from_rgb(reading.rgb.map(|c| c / reading.power))
+        } else {
+            Hsva::new(0.0, 0.0, 0.0, 1.0)
+        };
+        sensor.wavelength =
+            (reading.monochromatic > 0.0).then(|| reading.wavelength / reading.monochromatic);
+
+        let lit = sensor.power > sensor.threshold;
+        if lit == sensor.lit {
+            continue;
+        }
+        sensor.lit = lit;
+        events.send(LightSensorEvent {
+            entity: id,
+            lit,
+            power: sensor.power,
+        });
+
+        if sensor.drive_motors {
+            let own_motor = match motor_source {
+                Some(&UpdateFrom::Entity(motor, _)) => Some(motor),
+                _ => None,
+            };
+            let attached = children
+                .into_iter()
+                .flat_map(|kids| kids.iter())
+                .copied()
+                .chain(own_motor);
+            for motor_ent in attached {
+                let Ok(mut motor) = motors.get_mut(motor_ent) else { continue };
+                motor.enabled = lit;
+            }
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use bevy::math::{Quat, Vec2};
+use bevy::prelude::*;
+use bevy_prototype_lyon::prelude::Path;
+use bevy_rapier2d::prelude::*;
+use num_traits::FloatConst;
+
+use crate::objects::laser::LaserBundle;
+use crate::objects::lens::Lens;
+use crate::{systems, ToRot};
+
+systems!(process_mirror, event MirrorEvent);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MirrorAxis {
+    /// Flip left and right
+    Horizontal,
+    /// Flip top and bottom
+    Vertical,
+}
+
+impl MirrorAxis {
+    fn scale(&self) -> Vec2 {
+        match self {
+            MirrorAxis::Horizontal => Vec2::new(-1.0, 1.0),
+            MirrorAxis::Vertical => Vec2::new(1.0, -1.0),
+        }
+    }
+
+    /// Angle of a direction after mirroring it.
+    fn direction(&self, angle: f32) -> f32 {
+        match self {
+            MirrorAxis::Horizontal => f32::PI() - angle,
+            MirrorAxis::Vertical => -angle,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Event)]
+pub struct MirrorEvent {
+    pub entity: Entity,
+    pub axis: MirrorAxis,
+}
+
+/// Mirrors a collider in its local frame. Returns `None` for shapes that are symmetric
+/// (or that we don't know how to mirror), which can be kept as they are.
+fn mirror_shape(shape: ColliderView, scale: Vec2) -> Option<Collider> {
+    match shape {
+        ColliderView::Triangle(tri) => Some(Collider::triangle(
+            tri.a() * scale,
+            tri.b() * scale,
+            tri.c() * scale,
+        )),
+        ColliderView::ConvexPolygon(poly) => {
+            let points: Vec<_> = poly.points().map(|p| p * scale).collect();
+            Collider::convex_hull(&points)
+        }
+        ColliderView::Compound(compound) => Some(Collider::compound(
+            compound
+                .shapes()
+                .map(|(pos, rot, part)| {
+                    // M * (R(rot) * p + pos) = R(-rot) * (M * p) + M * pos
+                    let part = match part {
+                        ColliderView::Ball(ball) => Collider::ball(ball.radius()),
+                        ColliderView::Cuboid(cuboid) => {
+                            let half = cuboid.half_extents();
+                            Collider::cuboid(half.x, half.y)
+                        }
+                        part => mirror_shape(part, scale)?,
+                    };
+                    Some((pos * scale, -rot, part))
+                })
+                .collect::<Option<Vec<_>>>()?,
+        )),
+        _ => None,
+    }
+}
+
+pub fn process_mirror(
+    mut events: EventReader<MirrorEvent>,
+    mut xforms: Query<&mut Transform>,
+    mut shapes: Query<(Option<&Collider>, Option<&mut Path>, Option<&mut Velocity>)>,
+    lasers: Query<(), With<LaserBundle>>,
+    lenses: Query<(), With<Lens>>,
+    children: Query<&Children>,
+    mut impulse_joints: Query<(Entity, &mut ImpulseJoint)>,
+    mut multibody_joints: Query<(Entity, &mut MultibodyJoint)>,
+    mut commands: Commands,
+) {
+    for &MirrorEvent { entity, axis } in events.iter() {
+        let scale = axis.scale();
+        let Ok(mut xform) = xforms.get_mut(entity) else { continue };
+
+        if lasers.contains(entity) {
+            // a laser is just a direction
+            xform.rotation = Quat::from_rotation_z(axis.direction(xform.rotation.to_rot()));
+            continue;
+        }
+
+        if lenses.contains(entity) {
+            // lenses are symmetric around their axis, so they only need to be turned around
+            xform.rotation = Quat::from_rotation_z(axis.direction(xform.rotation.to_rot()));
+            continue;
+        }
+
+        info!("Mirroring {:?} ({:?})", entity, axis);
+
+        // M * R(a) = R(-a) * M, so mirroring the world shape around its center is the same as
+        // mirroring the local shape and negating the rotation
+        xform.rotation = Quat::from_rotation_z(-xform.rotation.to_rot());
+
+        if let Ok((collider, path, vel)) = shapes.get_mut(entity) {
+            if let Some(mirrored) = collider.and_then(|c| mirror_shape(c.as_typed_shape(), scale)) {
+                commands.entity(entity).insert(mirrored);
+            }
+            if let Some(mut path) = path {
+                path.0 = path
+                    .0
+                    .clone()
+                    .transformed(&lyon_path::math::Transform::scale(scale.x, scale.y));
+            }
+            if let Some(mut vel) = vel {
+                vel.linvel *= scale;
+                vel.angvel = -vel.angvel;
+            }
+        }
+
+        if let Ok(kids) = children.get(entity) {
+            for &child in kids.iter() {
+                if let Ok(mut child_xform) = xforms.get_mut(child) {
+                    child_xform.translation = (child_xform.translation.truncate() * scale)
+                        .extend(child_xform.translation.z);
+                    child_xform.rotation =
+                        Quat::from_rotation_z(axis.direction(child_xform.rotation.to_rot()));
+                }
+            }
+        }
+
+        macro_rules! mirror_joints {
+            ($joints:ident) => {
+                for (joint_ent, mut joint) in $joints.iter_mut() {
+                    if joint.parent == entity {
+                        let anchor = joint.data.local_anchor1() * scale;
+                        let basis = joint.data.local_basis1();
+                        joint.data.set_local_anchor1(anchor).set_local_basis1(axis.direction(basis));
+                    }
+                    if joint_ent == entity {
+                        let anchor = joint.data.local_anchor2() * scale;
+                        let basis = joint.data.local_basis2();
+                        joint.data.set_local_anchor2(anchor).set_local_basis2(axis.direction(basis));
+                    }
+                }
+            };
+        }
+
+        mirror_joints!(impulse_joints);
+        mirror_joints!(multibody_joints);
+    }
+}
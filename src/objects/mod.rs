@@ -4,7 +4,7 @@ use bevy::hierarchy::Parent;
 use bevy::math::Vec3;
 use bevy::prelude::{App, Component, Entity, Query, Ref, Sprite, Transform};
 use bevy_egui::egui::ecolor::Hsva;
-use bevy_rapier2d::prelude::ImpulseJoint;
+use bevy_rapier2d::prelude::{GenericJoint, ImpulseJoint, MultibodyJoint, Velocity};
 use bevy_rapier2d::rapier::dynamics::JointAxis;
 use bevy_rapier2d::rapier::prelude::MotorModel;
 use num_traits::FloatConst;
@@ -55,41 +55,68 @@ pub fn update_size_scales(
     }
 }
 
+fn apply_motor(joint: &mut GenericJoint, motor_component: &MotorComponent) {
+    joint.set_motor(
+        JointAxis::AngX,
+        0.0,
+        {
+            let vel = motor_component.vel * f32::PI() / 30.0;
+            if motor_component.reversed {
+                -vel
+            } else {
+                vel
+            }
+        },
+        0.0,
+        motor_component.torque,
+    );
+    joint
+        .raw
+        .set_motor_model(JointAxis::AngX.into(), MotorModel::ForceBased);
+    joint
+        .raw
+        .motor_axes
+        .set(JointAxis::AngX.into(), motor_component.enabled);
+}
+
 pub fn update_motors(
     mut motors: Query<(Entity, &mut ImpulseJoint, &UpdateFrom<MotorComponent>)>,
+    mut multibody_motors: Query<(Entity, &mut MultibodyJoint, &UpdateFrom<MotorComponent>)>,
     parents: Query<(Option<&Parent>, Option<Ref<MotorComponent>>)>,
 ) {
     for (entity, mut motor, update_source) in motors.iter_mut() {
         let (_, motor_component) = update_source
             .find_component(entity, &parents)
             .expect("motor not found");
-        motor.data.set_motor(
-            JointAxis::AngX,
-            0.0,
-            {
-                let vel = motor_component.vel * f32::PI() / 30.0;
-                if motor_component.reversed {
-                    -vel
-                } else {
-                    vel
-                }
-            },
-            0.0,
-            motor_component.torque,
-        );
-        motor
-            .data
-            .raw
-            .set_motor_model(JointAxis::AngX.into(), MotorModel::ForceBased);
-        motor
-            .data
-            .raw
-            .motor_axes
-            .set(JointAxis::AngX.into(), motor_component.enabled);
+        apply_motor(&mut motor.data, &motor_component);
+    }
+    // hinges between two bodies
+    for (entity, mut motor, update_source) in multibody_motors.iter_mut() {
+        let (_, motor_component) = update_source
+            .find_component(entity, &parents)
+            .expect("motor not found");
+        apply_motor(&mut motor.data, &motor_component);
     }
 }
 
-systems!(update_sprites_color, update_size_scales, update_motors);
+pub fn apply_velocity_locks(mut bodies: Query<(&LockedVelocity, &mut Velocity)>) {
+    for (lock, mut vel) in bodies.iter_mut() {
+        *vel = lock.0;
+    }
+}
+
+systems!(
+    update_sprites_color,
+    update_size_scales,
+    update_motors,
+    apply_velocity_locks,
+    mod controller,
+    mod csg,
+    mod lens,
+    mod light_sensor,
+    mod mirror,
+    mod text
+);
 
 #[derive(Component)]
 pub struct ColorComponent(pub Hsva);
@@ -147,3 +174,7 @@ impl SettingComponent for MotorComponent {
         *self
     }
 }
+
+/// Keeps the velocity of a body constant, whatever the forces applied to it
+#[derive(Component, Copy, Clone, Debug)]
+pub struct LockedVelocity(pub Velocity);
@@ -12,6 +12,7 @@ use bevy_rapier2d::geometry::{
 };
 use bevy_rapier2d::prelude::{ExternalForce, Sleeping};
 
+use crate::objects::laser::wavelength_to_color;
 use crate::objects::ColorComponent;
 use crate::update_from::UpdateFrom;
 use crate::FillStroke;
@@ -28,6 +29,7 @@ pub struct PhysicalObject {
     read_props: ReadMassProperties,
     groups: CollisionGroups,
     refractive_index: RefractiveIndex,
+    optical_material: OpticalMaterial,
     color: ColorComponent,
     color_upd: UpdateFrom<ColorComponent>,
     fill_stroke: FillStroke,
@@ -48,6 +50,7 @@ impl PhysicalObject {
             read_props: ReadMassProperties::default(),
             groups: CollisionGroups::new(Group::GROUP_1, Group::GROUP_1),
             refractive_index: RefractiveIndex::default(),
+            optical_material: OpticalMaterial::default(),
             color: ColorComponent(Hsva::new(0.0, 1.0, 1.0, 1.0)),
             color_upd: UpdateFrom::This,
             fill_stroke: FillStroke::default(),
@@ -108,11 +111,109 @@ impl PhysicalObject {
     }
 }
 
-#[derive(Component)]
-pub struct RefractiveIndex(pub(crate) f32);
+/// Wavelength at which the nominal index of a material is given (sodium D line), nm
+pub const REFERENCE_WAVELENGTH: f32 = 589.3;
+
+/// Refractive index as a function of the wavelength
+#[derive(Component, Copy, Clone, Debug, PartialEq)]
+pub enum RefractiveIndex {
+    /// Same index for every wavelength, infinite for opaque objects
+    Constant(f32),
+    /// n(λ) = A + B / λ², λ in µm
+    Cauchy { a: f32, b: f32 },
+    /// n²(λ) = 1 + Σ Bᵢ·λ² / (λ² − Cᵢ), λ in µm
+    Sellmeier { b: [f32; 3], c: [f32; 3] },
+}
+
+impl RefractiveIndex {
+    pub const VACUUM: Self = RefractiveIndex::Constant(1.0);
+
+    /// Index for a wavelength in nm.
+    pub fn at(&self, wavelength: f32) -> f32 {
+        let um = wavelength / 1000.0;
+        let sq = um * um;
+        match *self {
+            RefractiveIndex::Constant(n) => n,
+            RefractiveIndex::Cauchy { a, b } => a + b / sq,
+            RefractiveIndex::Sellmeier { b, c } => {
+                let n2 = 1.0 + b.iter().zip(c).map(|(b, c)| b * sq / (sq - c)).sum::<f32>();
+                n2.max(1.0).sqrt()
+            }
+        }
+    }
+
+    /// Index at the reference wavelength.
+    pub fn nominal(&self) -> f32 {
+        self.at(REFERENCE_WAVELENGTH)
+    }
+
+    pub fn is_opaque(&self) -> bool {
+        !self.nominal().is_finite()
+    }
+
+    pub fn is_dispersive(&self) -> bool {
+        !matches!(self, RefractiveIndex::Constant(_))
+    }
+
+    /// Abbe number, infinite for non-dispersive materials
+    pub fn abbe_number(&self) -> f32 {
+        // hydrogen F and C lines
+        (self.nominal() - 1.0) / (self.at(486.1) - self.at(656.3))
+    }
+}
+
+/// How an object interacts with light, besides its refractive index
+#[derive(Component, Copy, Clone, Debug, PartialEq)]
+pub struct OpticalMaterial {
+    /// Fraction of the light reflected by the surface as a mirror, the rest follows Fresnel's laws
+    pub reflectivity: f32,
+    /// Beer–Lambert attenuation coefficient, 1/m
+    pub absorption: f32,
+    /// Fraction of the reflected light scattered in all directions
+    pub diffuse: f32,
+    /// Color of the light let through, weighted by its alpha. When `None`, the object lets through
+    /// the fraction of light given by the transparency of its appearance.
+    pub filter: Option<Hsva>,
+}
+
+impl Default for OpticalMaterial {
+    fn default() -> Self {
+        Self {
+            reflectivity: 0.0,
+            absorption: 0.0,
+            diffuse: 0.0,
+            filter: None,
+        }
+    }
+}
+
+impl OpticalMaterial {
+    /// Whether the filter lets different wavelengths through differently.
+    pub fn is_tinted(&self) -> bool {
+        matches!(self.filter, Some(filter) if filter.a > 0.0 && filter.s > 0.0)
+    }
+
+    /// Fraction of the light of a wavelength (nm) let through the object, given its appearance.
+    pub fn transmission(&self, wavelength: f32, appearance: Hsva) -> f32 {
+        let Some(filter) = self.filter else { return 1.0 - appearance.a };
+        let light = wavelength_to_color(wavelength).to_rgb();
+        let total: f32 = light.iter().sum();
+        let passed = if total > 0.0 {
+            light.iter().zip(filter.to_rgb()).map(|(l, f)| l * f).sum::<f32>() / total
+        } else {
+            // outside of the visible range, use the overall brightness
+            filter.v
+        };
+        (1.0 - filter.a) + filter.a * passed
+    }
+}
 
 impl Default for RefractiveIndex {
+    /// Crown glass (BK7)
     fn default() -> Self {
-        RefractiveIndex(1.5)
+        RefractiveIndex::Cauchy {
+            a: 1.5046,
+            b: 0.0042,
+        }
     }
 }
@@ -0,0 +1,93 @@
+use bevy::prelude::*;
+use bevy::text::TextLayoutInfo;
+use bevy_prototype_lyon::geometry::GeometryBuilder;
+use bevy_prototype_lyon::shapes;
+use bevy_rapier2d::geometry::Collider;
+
+use crate::measures::SimulationTime;
+use crate::objects::ColorComponent;
+use crate::palette::ToRgba;
+use crate::systems;
+use crate::ui::windows::object::plot::{find_quantity, PlotQuery};
+
+systems!(update_texts, update_text_bounds);
+
+/// Font size the glyphs are rendered at, the label is then scaled down to its actual size
+pub const TEXT_RESOLUTION: f32 = 64.0;
+
+/// Text label placed in the scene, or attached to a body if it's its child.
+/// The actual glyphs are drawn by a `Text2dBundle` child.
+#[derive(Component, Clone, Debug)]
+pub struct TextObject {
+    pub text: String,
+    /// Name of a plot quantity whose value is displayed after the text
+    pub expression: Option<String>,
+    pub decimals: usize,
+}
+
+impl Default for TextObject {
+    fn default() -> Self {
+        Self {
+            text: "Text".to_string(),
+            expression: None,
+            decimals: 2,
+        }
+    }
+}
+
+impl TextObject {
+    fn display(&self, body: Option<PlotQuery>, time: f32) -> String {
+        let Some(name) = &self.expression else {
+            return self.text.clone();
+        };
+        let value = match (find_quantity(name), body) {
+            (Some(quantity), Some(body)) => match quantity.measure_body(time, body) {
+                Some(value) => format!("{:.*}", self.decimals, value),
+                None => "?".to_string(),
+            },
+            _ => "?".to_string(),
+        };
+        format!("{}{}", self.text, value)
+    }
+}
+
+pub fn update_texts(
+    mut labels: Query<(&Parent, &mut Text)>,
+    objects: Query<(&TextObject, &ColorComponent, Option<&Parent>)>,
+    bodies: Query<PlotQuery>,
+    sim: Res<SimulationTime>,
+) {
+    for (parent, mut text) in labels.iter_mut() {
+        let Ok((object, color, body)) = objects.get(parent.get()) else { continue };
+        let body = body.and_then(|body| bodies.get(body.get()).ok());
+        let value = object.display(body, sim.time);
+        let color = color.0.to_rgba();
+        let section = &text.sections[0];
+        if section.value != value || section.style.color != color {
+            let section = &mut text.sections[0];
+            section.value = value;
+            section.style.color = color;
+        }
+    }
+}
+
+/// Fits the selection outline and the collider of text objects to their text.
+pub fn update_text_bounds(
+    layouts: Query<(&Parent, &TextLayoutInfo), Changed<TextLayoutInfo>>,
+    objects: Query<(), With<TextObject>>,
+    mut commands: Commands,
+) {
+    for (parent, layout) in layouts.iter() {
+        if !objects.contains(parent.get()) {
+            continue;
+        }
+        let size = (layout.size / TEXT_RESOLUTION).max(Vec2::splat(0.1));
+        commands.entity(parent.get()).insert((
+            Collider::cuboid(size.x / 2.0, size.y / 2.0),
+            GeometryBuilder::build_as(&shapes::Rectangle {
+                extents: size * 1.1, // make selection display a bit bigger
+                ..Default::default()
+            }),
+        ));
+    }
+}
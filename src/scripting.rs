@@ -0,0 +1,390 @@
+use std::sync::{Arc, Mutex};
+
+use bevy::math::Vec2;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_egui::EguiContexts;
+use bevy_rapier2d::prelude::*;
+use rhai::{CallFnOptions, Dynamic, Engine, Map, Scope, AST, FLOAT, INT};
+
+use crate::objects::laser::LaserHitEvent;
+use crate::objects::light_sensor::LightSensorEvent;
+use crate::objects::{ColorComponent, MotorComponent};
+use crate::tools::add_object::AddObjectEvent;
+use crate::{systems, ToRot};
+
+systems!(run_scripts, enable_collision_events);
+
+/// Handlers a script can define, with their arguments
+pub const SCRIPT_HANDLERS: &[(&str, &str)] = &[
+    ("on_step", "dt"),
+    ("on_key", "key"),
+    ("on_collide", "other"),
+    ("on_laser_hit", "strength"),
+    ("on_light", "lit"),
+];
+
+/// Properties of `this`, as seen by handlers
+pub const SCRIPT_PROPERTIES: &[&str] = &[
+    "id",
+    "x",
+    "y",
+    "angle",
+    "vx",
+    "vy",
+    "angvel",
+    "hue",
+    "sat",
+    "val",
+    "alpha",
+    "density",
+    "friction",
+    "restitution",
+    "motor_speed",
+    "vars",
+];
+
+enum ScriptCommand {
+    Add(AddObjectEvent),
+    Despawn(INT),
+}
+
+#[derive(Resource)]
+pub struct ScriptEngine {
+    engine: Engine,
+    commands: Arc<Mutex<Vec<ScriptCommand>>>,
+}
+
+impl Default for ScriptEngine {
+    fn default() -> Self {
+        let mut engine = Engine::new();
+        // scripts come from scene files, don't let them hang or exhaust the app
+        engine
+            .set_max_operations(100_000)
+            .set_max_call_levels(32)
+            .set_max_expr_depths(64, 32)
+            .set_max_string_size(10_000)
+            .set_max_array_size(10_000)
+            .set_max_map_size(10_000)
+            .disable_symbol("eval")
+            .on_print(|text| info!("script: {}", text))
+            .on_debug(|text, _, pos| info!("script ({}): {}", pos, text));
+
+        let commands = Arc::new(Mutex::new(Vec::new()));
+
+        let queue = commands.clone();
+        engine.register_fn("spawn_box", move |x: FLOAT, y: FLOAT, w: FLOAT, h: FLOAT| {
+            queue.lock().unwrap().push(ScriptCommand::Add(AddObjectEvent::Box {
+                pos: Vec2::new(x as f32, y as f32),
+                size: Vec2::new(w as f32, h as f32),
+            }));
+        });
+        let queue = commands.clone();
+        engine.register_fn("spawn_ball", move |x: FLOAT, y: FLOAT, radius: FLOAT| {
+            queue.lock().unwrap().push(ScriptCommand::Add(AddObjectEvent::Circle {
+                center: Vec2::new(x as f32, y as f32),
+                radius: radius as f32,
+            }));
+        });
+        let queue = commands.clone();
+        engine.register_fn("despawn", move |id: INT| {
+            queue.lock().unwrap().push(ScriptCommand::Despawn(id));
+        });
+
+        Self { engine, commands }
+    }
+}
+
+impl ScriptEngine {
+    pub fn compile(&self, source: &str) -> Result<AST, String> {
+        self.engine.compile(source).map_err(|err| err.to_string())
+    }
+}
+
+#[derive(Component, Default)]
+pub struct ScriptComponent {
+    pub source: String,
+    ast: Option<AST>,
+    pub error: Option<String>,
+    /// Per-object variables, available as `this.vars`
+    vars: Map,
+}
+
+impl ScriptComponent {
+    pub fn new(source: String, ast: AST) -> Self {
+        Self {
+            source,
+            ast: Some(ast),
+            error: None,
+            vars: Map::new(),
+        }
+    }
+
+    pub fn handlers(&self) -> Vec<&str> {
+        match &self.ast {
+            Some(ast) => SCRIPT_HANDLERS
+                .iter()
+                .map(|(name, _)| *name)
+                .filter(|name| ast.iter_functions().any(|f| f.name == *name))
+                .collect(),
+            None => vec![],
+        }
+    }
+}
+
+fn number(value: Option<&Dynamic>) -> Option<f32> {
+    let value = value?;
+    value
+        .as_float()
+        .ok()
+        .or_else(|| value.as_int().ok().map(|i| i as FLOAT))
+        .map(|f| f as f32)
+}
+
+pub fn run_scripts(
+    engine: Res<ScriptEngine>,
+    mut scripts: Query<(
+        Entity,
+        &mut ScriptComponent,
+        &mut Transform,
+        Option<&mut Velocity>,
+        Option<&mut ColorComponent>,
+        Option<&mut ColliderMassProperties>,
+        Option<&mut Friction>,
+        Option<&mut Restitution>,
+        Option<&Children>,
+    )>,
+    mut motors: Query<&mut MotorComponent>,
+    bodies: Query<(), With<RigidBody>>,
+    keys: Res<Input<KeyCode>>,
+    mut collisions: EventReader<CollisionEvent>,
+    mut laser_hits: EventReader<LaserHitEvent>,
+    mut light_events: EventReader<LightSensorEvent>,
+    mut egui_ctx: EguiContexts,
+    time: Res<Time>,
+    rapier_conf: Res<RapierConfiguration>,
+    mut add_obj: EventWriter<AddObjectEvent>,
+    mut commands: Commands,
+) {
+    let mut calls: HashMap<Entity, Vec<(&'static str, Dynamic)>> = HashMap::new();
+
+    for event in collisions.iter() {
+        if let CollisionEvent::Started(a, b, _) = *event {
+            for (this, other) in [(a, b), (b, a)] {
+                calls
+                    .entry(this)
+                    .or_default()
+                    .push(("on_collide", (other.to_bits() as INT).into()));
+            }
+        }
+    }
+
+    for hit in laser_hits.iter() {
+        calls
+            .entry(hit.entity)
+            .or_default()
+            .push(("on_laser_hit", (hit.strength as FLOAT).into()));
+    }
+
+    for event in light_events.iter() {
+        calls
+            .entry(event.entity)
+            .or_default()
+            .push(("on_light", event.lit.into()));
+    }
+
+    let typing = egui_ctx.ctx_mut().wants_keyboard_input();
+    let pressed_keys: Vec<Dynamic> = if typing {
+        vec![]
+    } else {
+        keys.get_just_pressed()
+            .map(|key| format!("{:?}", key).into())
+            .collect()
+    };
+
+    for (id, mut script, mut xform, vel, color, mass, friction, restitution, children) in
+        scripts.iter_mut()
+    {
+        if script.error.is_some() || script.source.is_empty() {
+            continue;
+        }
+        if script.ast.is_none() {
+            // freshly loaded from a scene
+            match engine.compile(&script.source) {
+                Ok(ast) => script.ast = Some(ast),
+                Err(err) => {
+                    script.error = Some(err);
+                    continue;
+                }
+            }
+        }
+
+        let mut events = calls.remove(&id).unwrap_or_default();
+        events.extend(pressed_keys.iter().map(|key| ("on_key", key.clone())));
+        if rapier_conf.physics_pipeline_active {
+            events.push(("on_step", (time.delta_seconds() as FLOAT).into()));
+        }
+
+        let handlers = script.handlers();
+        events.retain(|(name, _)| handlers.contains(name));
+        if events.is_empty() {
+            continue;
+        }
+
+        let density = match mass.as_deref() {
+            Some(ColliderMassProperties::Density(density)) => Some(*density),
+            _ => None,
+        };
+        let motor_ents: Vec<Entity> = children
+            .into_iter()
+            .flat_map(|kids| kids.iter())
+            .copied()
+            .filter(|kid| motors.contains(*kid))
+            .collect();
+        let motor_speed = motor_ents
+            .first()
+            .map(|&motor| motors.get(motor).unwrap().vel);
+
+        let mut before = Map::new();
+        let mut set = |name: &str, value: Option<f32>| {
+            if let Some(value) = value {
+                before.insert(name.into(), (value as FLOAT).into());
+            }
+        };
+        set("x", Some(xform.translation.x));
+        set("y", Some(xform.translation.y));
+        set("angle", Some(xform.rotation.to_rot()));
+        set("vx", vel.as_ref().map(|v| v.linvel.x));
+        set("vy", vel.as_ref().map(|v| v.linvel.y));
+        set("angvel", vel.as_ref().map(|v| v.angvel));
+        set("hue", color.as_ref().map(|c| c.0.h));
+        set("sat", color.as_ref().map(|c| c.0.s));
+        set("val", color.as_ref().map(|c| c.0.v));
+        set("alpha", color.as_ref().map(|c| c.0.a));
+        set("density", density);
+        set("friction", friction.as_ref().map(|f| f.coefficient));
+        set("restitution", restitution.as_ref().map(|r| r.coefficient));
+        set("motor_speed", motor_speed);
+        before.insert("id".into(), (id.to_bits() as INT).into());
+
+        let mut this_map = before.clone();
+        this_map.insert("vars".into(), script.vars.clone().into());
+        let mut this: Dynamic = this_map.into();
+
+        let ast = script.ast.as_ref().unwrap();
+        let mut scope = Scope::new();
+        let mut error = None;
+        for (name, arg) in events {
+            let options = CallFnOptions::new()
+                .eval_ast(false)
+                .bind_this_ptr(&mut this);
+            if let Err(err) =
+                engine
+                    .engine
+                    .call_fn_with_options::<Dynamic>(options, &mut scope, ast, name, (arg,))
+            {
+                error = Some(format!("{}: {}", name, err));
+                break;
+            }
+        }
+
+        if let Some(error) = error {
+            info!("Script of {:?} failed: {}", id, error);
+            script.error = Some(error);
+            script.ast = None;
+            continue;
+        }
+
+        let Some(after) = this.try_cast::<Map>() else {
+            script.error = Some("`this` must stay an object".to_string());
+            continue;
+        };
+        if let Some(vars) = after.get("vars").and_then(|vars| vars.clone().try_cast::<Map>()) {
+            script.vars = vars;
+        }
+
+        // only write back what the script changed, not to disturb the simulation
+        let changed = |name: &str| {
+            let new = number(after.get(name))?;
+            (Some(new) != number(before.get(name))).then_some(new)
+        };
+        if let Some(x) = changed("x") {
+            xform.translation.x = x;
+        }
+        if let Some(y) = changed("y") {
+            xform.translation.y = y;
+        }
+        if let Some(angle) = changed("angle") {
+            xform.rotation = Quat::from_rotation_z(angle);
+        }
+        if let Some(mut vel) = vel {
+            if let Some(vx) = changed("vx") {
+                vel.linvel.x = vx;
+            }
+            if let Some(vy) = changed("vy") {
+                vel.linvel.y = vy;
+            }
+            if let Some(angvel) = changed("angvel") {
+                vel.angvel = angvel;
+            }
+        }
+        if let Some(mut color) = color {
+            if let Some(h) = changed("hue") {
+                color.0.h = h.rem_euclid(1.0);
+            }
+            if let Some(s) = changed("sat") {
+                color.0.s = s.clamp(0.0, 1.0);
+            }
+            if let Some(v) = changed("val") {
+                color.0.v = v.clamp(0.0, 1.0);
+            }
+            if let Some(a) = changed("alpha") {
+                color.0.a = a.clamp(0.0, 1.0);
+            }
+        }
+        if let (Some(mut mass), Some(density)) = (mass, changed("density")) {
+            *mass = ColliderMassProperties::Density(density.max(0.0));
+        }
+        if let (Some(mut friction), Some(coefficient)) = (friction, changed("friction")) {
+            friction.coefficient = coefficient.max(0.0);
+        }
+        if let (Some(mut restitution), Some(coefficient)) = (restitution, changed("restitution")) {
+            restitution.coefficient = coefficient.clamp(0.0, 1.0);
+        }
+        if let Some(speed) = changed("motor_speed") {
+            for &motor in &motor_ents {
+                motors.get_mut(motor).unwrap().vel = speed;
+            }
+        }
+    }
+
+    for command in engine.commands.lock().unwrap().drain(..) {
+        match command {
+            ScriptCommand::Add(event) => add_obj.send(event),
+            ScriptCommand::Despawn(id) => {
+                // scripts may only remove scene objects, not windows, tools or the scene itself
+                let ent = Entity::from_bits(id as u64);
+                if bodies.contains(ent) {
+                    commands.entity(ent).despawn_recursive();
+                } else {
+                    info!("Script tried to despawn {:?}, which is not an object", ent);
+                }
+            }
+        }
+    }
+}
+
+/// Bodies with a script need to report their collisions
+pub fn enable_collision_events(
+    mut scripts: Query<(Entity, Option<&mut ActiveEvents>), Added<ScriptComponent>>,
+    mut commands: Commands,
+) {
+    for (id, events) in scripts.iter_mut() {
+        match events {
+            Some(mut events) => *events |= ActiveEvents::COLLISION_EVENTS,
+            None => {
+                commands.entity(id).insert(ActiveEvents::COLLISION_EVENTS);
+            }
+        }
+    }
+}
@@ -1,18 +1,22 @@
 use crate::mouse::select;
 use crate::mouse::select::SelectUnderMouseEvent;
 use crate::objects::hinge::HingeObject;
-use crate::objects::laser::LaserBundle;
-use crate::objects::phy_obj::PhysicalObject;
+use crate::objects::lens::{self, LensKind};
+use crate::objects::laser::{Emitter, LaserBundle, Spectrum, LASER_WIDTH};
+use crate::objects::phy_obj::{PhysicalObject, RefractiveIndex, REFERENCE_WAVELENGTH};
+use crate::objects::text::{TextObject, TEXT_RESOLUTION};
 use crate::objects::{ColorComponent, MotorComponent, SettingComponent, SizeComponent, SpriteOnly};
 use crate::palette::PaletteConfig;
 use crate::ui::images::AppIcons;
 use crate::ui::UiState;
 use crate::update_from::UpdateFrom;
-use crate::BORDER_THICKNESS;
+use crate::{InvTransformPoint, BORDER_THICKNESS};
 use bevy::hierarchy::BuildChildren;
 use bevy::log::info;
 use bevy::math::{Vec2, Vec3, Vec3Swizzles};
-use bevy::prelude::{Color, Entity, Event, SpatialBundle, Sprite, SpriteBundle};
+use bevy::prelude::{
+    Color, Entity, Event, SpatialBundle, Sprite, SpriteBundle, Text2dBundle, TextStyle,
+};
 use bevy::prelude::{
     Commands, EventReader, EventWriter, Local, Query, Res, Transform, With, Without,
 };
@@ -25,7 +29,7 @@ use bevy_rapier2d::dynamics::{
     FixedJointBuilder, ImpulseJoint, MultibodyJoint, RevoluteJointBuilder,
 };
 use bevy_rapier2d::geometry::Sensor;
-use bevy_rapier2d::geometry::{ActiveHooks, Collider};
+use bevy_rapier2d::geometry::{ActiveHooks, Collider, ColliderMassProperties};
 use bevy_rapier2d::pipeline::QueryFilter;
 use bevy_rapier2d::plugin::RapierContext;
 use bevy_turborand::RngComponent;
@@ -43,8 +47,10 @@ pub enum AddObjectEvent {
     Fix(Vec2),
     Circle { center: Vec2, radius: f32 },
     Box { pos: Vec2, size: Vec2 },
-    Laser(Vec2),
+    Laser(Vec2, Emitter),
+    Text(Vec2),
     Polygon { pos: Vec2, points: Vec<Vec2> },
+    Lens { pos: Vec2, kind: LensKind },
 }
 
 const DEFAULT_OBJ_SIZE: f32 = 66.0;
@@ -97,6 +103,21 @@ pub fn process_add_object(
                     )
                     .log_components();
             }
+            Lens { pos, kind } => {
+                let index = RefractiveIndex::default().at(REFERENCE_WAVELENGTH);
+                let lens = lens::Lens::new(kind, 1.0, index);
+                commands
+                    .spawn(lens.physical_object(z.pos(pos)))
+                    .set_parent(ui_state.scene)
+                    .insert((
+                        lens,
+                        kind.material(),
+                        // optical benches stay where they are put
+                        RigidBody::Fixed,
+                        ColorComponent(kind.color()).update_from_this(),
+                    ))
+                    .log_components();
+            }
             Fix(pos) => {
                 let (entity1, entity2) = {
                     let mut entities = select::find_under_mouse(
@@ -328,7 +349,7 @@ pub fn process_add_object(
                     }
                 }
             }
-            Laser(pos) => {
+            Laser(pos, emitter) => {
                 let entity =
                     select::find_under_mouse(&rapier, pos, QueryFilter::only_dynamic(), |ent| {
                         query.get(ent).unwrap().0.translation.z
@@ -336,13 +357,12 @@ pub fn process_add_object(
                     .next();
 
                 let scale = cameras.single_mut().scale.x * DEFAULT_OBJ_SIZE;
+                let color = palette.get_color_hsva_opaque(&mut *rng.single_mut());
                 let laser = commands
                     .spawn((
-                        LaserBundle {
-                            fade_distance: 10.0,
-                        },
-                        ColorComponent(palette.get_color_hsva_opaque(&mut *rng.single_mut()))
-                            .update_from_this(),
+                        LaserBundle::new(Spectrum::from_color(color), scale * LASER_WIDTH)
+                            .with_emitter(emitter),
+                        ColorComponent(color).update_from_this(),
                         Collider::cuboid(0.5, 0.25),
                         SizeComponent(scale),
                         Sensor,
@@ -385,6 +405,69 @@ pub fn process_add_object(
                         ));
                     });
             }
+            Text(pos) => {
+                let entity =
+                    select::find_under_mouse(&rapier, pos, QueryFilter::only_dynamic(), |ent| {
+                        query.get(ent).unwrap().0.translation.z
+                    })
+                    .next();
+
+                const TEXT_SIZE: f32 = 24.0;
+                let scale = cameras.single_mut().scale.x * TEXT_SIZE;
+                let text = commands
+                    .spawn((
+                        TextObject::default(),
+                        ColorComponent(palette.get_color_hsva_opaque(&mut *rng.single_mut()))
+                            .update_from_this(),
+                        // resized to fit the text once it's laid out
+                        Collider::cuboid(0.5, 0.5),
+                        ColliderMassProperties::Density(0.0),
+                        SizeComponent(scale),
+                        Sensor,
+                        SpriteOnly,
+                    ))
+                    .set_parent(ui_state.scene)
+                    .id();
+
+                let text_pos = if let Some(entity) = entity {
+                    commands.entity(entity).add_child(text);
+                    query.get(entity).unwrap().0.to_local(pos)
+                } else {
+                    pos
+                };
+                commands
+                    .entity(text)
+                    .insert((
+                        ShapeBundle {
+                            path: GeometryBuilder::build_as(&shapes::Rectangle {
+                                extents: Vec2::ONE,
+                                ..Default::default()
+                            }),
+                            transform: Transform::from_translation(z.pos(text_pos)),
+                            ..Default::default()
+                        },
+                        crate::make_stroke(Color::rgba(0.0, 0.0, 0.0, 0.0), BORDER_THICKNESS),
+                        UpdateFrom::<SizeComponent>::This,
+                    ))
+                    .with_children(|builder| {
+                        builder.spawn(Text2dBundle {
+                            // `Text` is shadowed by the event variant
+                            text: bevy::text::Text::from_section(
+                                "",
+                                TextStyle {
+                                    font_size: TEXT_RESOLUTION,
+                                    ..Default::default()
+                                },
+                            ),
+                            transform: Transform::from_scale(Vec3::new(
+                                1.0 / TEXT_RESOLUTION,
+                                1.0 / TEXT_RESOLUTION,
+                                1.0,
+                            )),
+                            ..Default::default()
+                        });
+                    });
+            }
             ref x => unimplemented!("unimplemented tool {:?}", x),
         }
     }
@@ -26,9 +26,11 @@ mod separator_custom;
 mod text_button;
 mod tabs;
 mod custom_widget;
+mod dial;
 
 systems! {
     mod windows,
+    mod vectors,
     ui_example,
     process_temporary_windows,
     remove_temporary_windows,
@@ -167,7 +169,8 @@ pub fn handle_context_menu(
     mut ev: EventReader<ContextMenuEvent>,
     ui: ResMut<UiState>,
     mut commands: Commands,
-    existing: Query<Entity, With<MenuWindow>>
+    existing: Query<Entity, With<MenuWindow>>,
+    mouse: Res<MousePosWorld>,
 ) {
     for ev in ev.iter() {
         let entity = ui.selected_entity.map(|sel| sel.entity);
@@ -176,7 +179,7 @@ pub fn handle_context_menu(
             commands.entity(existing).despawn_recursive();
         }
         let wnd = commands
-            .spawn((MenuWindow::default(), InitialPos::initial(ev.screen_pos)))
+            .spawn((MenuWindow::at(mouse.xy()), InitialPos::initial(ev.screen_pos)))
             .id();
 
         if let Some(id) = entity {
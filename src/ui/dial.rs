@@ -0,0 +1,45 @@
+use bevy_egui::egui;
+use bevy_egui::egui::{vec2, Response, Sense, Ui, Widget, WidgetInfo, WidgetType};
+
+/// Round knob for editing an angle (in radians, counterclockwise from the right).
+pub struct Dial<'a> {
+    angle: &'a mut f32,
+    size: f32,
+}
+
+impl<'a> Dial<'a> {
+    pub fn new(angle: &'a mut f32, size: f32) -> Self {
+        Self { angle, size }
+    }
+}
+
+impl<'a> Widget for Dial<'a> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let Self { angle, size } = self;
+        let (rect, mut response) = ui.allocate_exact_size(egui::Vec2::splat(size), Sense::click_and_drag());
+        response.widget_info(|| WidgetInfo::new(WidgetType::Slider));
+
+        if let Some(pos) = response.interact_pointer_pos() {
+            let delta = pos - rect.center();
+            // screen y goes down
+            let new_angle = (-delta.y).atan2(delta.x);
+            if delta.length() > 1.0 && new_angle != *angle {
+                *angle = new_angle;
+                response.mark_changed();
+            }
+        }
+
+        if ui.is_rect_visible(rect) {
+            let visuals = ui.style().interact(&response);
+            let center = rect.center();
+            let radius = size / 2.0 - visuals.fg_stroke.width;
+            let painter = ui.painter();
+            painter.circle(center, radius, visuals.bg_fill, visuals.bg_stroke);
+            let tip = center + vec2(angle.cos(), -angle.sin()) * radius;
+            painter.line_segment([center, tip], visuals.fg_stroke);
+            painter.circle_filled(center, visuals.fg_stroke.width * 1.5, visuals.fg_stroke.color);
+        }
+
+        response
+    }
+}
@@ -0,0 +1,149 @@
+use bevy::math::Vec3Swizzles;
+use bevy::prelude::*;
+use bevy_egui::egui::{Align2, Color32, FontId, LayerId};
+use bevy_egui::{egui, EguiContexts, EguiSettings};
+use bevy_mouse_tracking_plugin::MainCamera;
+use bevy_rapier2d::prelude::{ReadMassProperties, Velocity};
+use std::f32::consts::TAU;
+
+use crate::config::AppConfig;
+use crate::measures::{ForceKind, ForceValue, Forces, Momentum};
+use crate::palette::{ObjectAppearance, PaletteConfig};
+use crate::{draw_arrow, systems, InvTransformPoint, FOREGROUND_Z};
+
+systems!(draw_vectors);
+
+/// Per-object override of the palette's `show_forces`, `show_velocities` and `show_momentums`
+#[derive(Component, Copy, Clone, Debug, PartialEq)]
+pub struct VectorDisplay {
+    pub forces: bool,
+    pub velocities: bool,
+    pub momentums: bool,
+}
+
+impl From<ObjectAppearance> for VectorDisplay {
+    fn from(appearance: ObjectAppearance) -> Self {
+        Self {
+            forces: appearance.show_forces,
+            velocities: appearance.show_velocities,
+            momentums: appearance.show_momentums,
+        }
+    }
+}
+
+/// Radius of the arc representing a torque, m
+const TORQUE_RADIUS: f32 = 0.3;
+
+fn force_color(kind: ForceKind) -> Color {
+    match kind {
+        ForceKind::Gravity => Color::GREEN,
+        ForceKind::Torque => Color::WHITE,
+        ForceKind::Contact => Color::ORANGE,
+        ForceKind::Friction => Color::YELLOW,
+        ForceKind::Joint => Color::PURPLE,
+        ForceKind::Motor => Color::CYAN,
+        ForceKind::Custom => Color::RED,
+        ForceKind::Drag => Color::GRAY,
+        ForceKind::Unexplained => Color::PINK,
+    }
+}
+
+/// Arc around `center`, whose angle is proportional to the torque, counterclockwise if positive.
+fn draw_torque(gizmos: &mut Gizmos, center: Vec2, angle: f32, color: Color) -> Vec2 {
+    let angle = angle.clamp(-0.9 * TAU, 0.9 * TAU);
+    let point = |a: f32| (center + Vec2::from_angle(a) * TORQUE_RADIUS).extend(FOREGROUND_Z);
+    let steps = ((angle.abs() / TAU * 32.0).ceil() as usize).max(1);
+    for i in 0..steps {
+        let a0 = angle * i as f32 / steps as f32;
+        let a1 = angle * (i + 1) as f32 / steps as f32;
+        gizmos.line(point(a0), point(a1), color);
+    }
+    let end = point(angle).xy();
+    let tangent = Vec2::from_angle(angle).perp() * angle.signum();
+    draw_arrow(gizmos, end - tangent * 0.1, end, color);
+    end
+}
+
+pub fn draw_vectors(
+    bodies: Query<(
+        &Transform,
+        &ReadMassProperties,
+        Option<&Forces>,
+        Option<&Velocity>,
+        Option<&Momentum>,
+        Option<&VectorDisplay>,
+    )>,
+    cameras: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    palette: Res<PaletteConfig>,
+    config: Res<AppConfig>,
+    egui_settings: Res<EguiSettings>,
+    mut egui_ctx: EguiContexts,
+    mut gizmos: Gizmos,
+) {
+    let default = VectorDisplay::from(palette.current_palette.object_appearance);
+    let Ok((camera, camera_pos)) = cameras.get_single() else { return };
+    let painter = egui_ctx
+        .ctx_mut()
+        .layer_painter(LayerId::background());
+    let label = |at: Vec2, text: String, color: Color| {
+        if !config.vector_labels {
+            return;
+        }
+        let Some(pos) = camera.world_to_viewport(camera_pos, at.extend(0.0)) else { return };
+        let pos = pos / egui_settings.scale_factor as f32;
+        let [r, g, b, _] = color.as_rgba_u8();
+        painter.text(
+            egui::pos2(pos.x, pos.y),
+            Align2::LEFT_BOTTOM,
+            text,
+            FontId::proportional(12.0),
+            Color32::from_rgb(r, g, b),
+        );
+    };
+
+    for (xform, ReadMassProperties(mass), forces, vel, momentum, display) in bodies.iter() {
+        let display = display.copied().unwrap_or(default);
+        let center = xform.to_global(mass.local_center_of_mass);
+
+        if display.forces {
+            for force in forces.iter().flat_map(|f| f.iter()) {
+                let color = force_color(force.kind);
+                let name: &'static str = force.kind.into();
+                match force.value {
+                    ForceValue::Force(value) => {
+                        if value == Vec2::ZERO {
+                            continue;
+                        }
+                        let from = xform.to_global(force.at);
+                        let to = from + value * config.force_scale;
+                        draw_arrow(&mut gizmos, from, to, color);
+                        label(to, format!("{} {:.2} N", name, value.length()), color);
+                    }
+                    ForceValue::Torque(value) => {
+                        if value == 0.0 {
+                            continue;
+                        }
+                        let end = draw_torque(&mut gizmos, center, value * config.force_scale, color);
+                        label(end, format!("{} {:.2} N·m", name, value), color);
+                    }
+                }
+            }
+        }
+
+        if display.velocities {
+            if let Some(vel) = vel {
+                let to = center + vel.linvel * config.velocity_scale;
+                draw_arrow(&mut gizmos, center, to, Color::BLUE);
+                label(to, format!("{:.2} m/s", vel.linvel.length()), Color::BLUE);
+            }
+        }
+
+        if display.momentums {
+            if let Some(momentum) = momentum {
+                let to = center + momentum.linear * config.momentum_scale;
+                draw_arrow(&mut gizmos, center, to, Color::FUCHSIA);
+                label(to, format!("{:.2} kg·m/s", momentum.linear.length()), Color::FUCHSIA);
+            }
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use crate::objects::laser::LaserBundle;
+use crate::objects::laser::{Emitter, LaserBundle};
+use crate::objects::lens::{Lens, LensKind};
+use crate::objects::mirror::{MirrorAxis, MirrorEvent};
+use crate::tools::add_object::AddObjectEvent;
 use crate::objects::{ColorComponent, MotorComponent};
 use crate::ui::images::GuiIcons;
 use crate::ui::{InitialPos, Subwindow, TemporaryWindow};
@@ -21,14 +24,18 @@ use crate::ui::windows::object::controller::ControllerWindow;
 use crate::ui::windows::object::geom_actions::GeometryActionsWindow;
 use crate::ui::windows::object::information::InformationWindow;
 use crate::ui::windows::object::laser::LaserWindow;
+use crate::ui::windows::object::lens::LensWindow;
+use crate::ui::windows::object::light_sensor::LightSensorWindow;
 use crate::ui::windows::object::material::MaterialWindow;
 use crate::ui::windows::object::plot::PlotWindow;
 use crate::ui::windows::object::script::ScriptMenuWindow;
 use crate::ui::windows::object::selection::SelectionWindow;
+use crate::ui::windows::object::text::TextWindow;
 
 use crate::ui::windows::object::velocities::VelocitiesWindow;
 
 use crate::ui::windows::scene::background::BackgroundWindow;
+use crate::ui::windows::scene::statistics::StatisticsWindow;
 
 use crate::ui::menu_item::MenuItem;
 use crate::ui::windows::object::hinge::HingeWindow;
@@ -43,9 +50,18 @@ systems! {
 pub struct MenuWindow {
     hovered_item: Option<(MenuId, Duration)>,
     selected_item: Option<(MenuId, Entity)>,
+    /// Where the menu was opened, in the scene
+    world_pos: Vec2,
 }
 
 impl MenuWindow {
+    pub fn at(world_pos: Vec2) -> Self {
+        Self {
+            world_pos,
+            ..Default::default()
+        }
+    }
+
     fn show(
         mut wnds: Query<(Entity, Option<&Parent>, &mut MenuWindow, &mut InitialPos)>,
         is_temp: Query<Option<&TemporaryWindow>>,
@@ -60,9 +76,12 @@ impl MenuWindow {
             Option<&LaserBundle>,
             Option<&RigidBody>,
             Option<&MotorComponent>,
+            Option<&Lens>,
         )>,
         mut cameras: Query<&mut Transform, With<MainCamera>>,
-        mut zoom2scene: EventWriter<ZoomToScene>
+        mut zoom2scene: EventWriter<ZoomToScene>,
+        mut mirror: EventWriter<MirrorEvent>,
+        mut add_obj: EventWriter<AddObjectEvent>,
     ) {
         let ctx = egui_ctx.ctx_mut();
         for (wnd_id, entity, mut info_wnd, mut initial_pos) in wnds.iter_mut() {
@@ -147,7 +166,14 @@ impl MenuWindow {
                             if item!("Erase", erase) {
                                 commands.entity(id).despawn_recursive();
                             }
-                            if item!("Mirror", mirror) {}
+                            if item!("Mirror", mirror) {
+                                let axis = if ui.input(|i| i.modifiers.shift) {
+                                    MirrorAxis::Vertical
+                                } else {
+                                    MirrorAxis::Horizontal
+                                };
+                                mirror.send(MirrorEvent { entity: id, axis });
+                            }
                             if item!("Show plot", plot) {
                                 commands.entity(id).with_children(|parent| {
                                     parent.spawn((PlotWindow::default(), InitialPos::persistent(pos2(100.0, 100.0))));
@@ -160,7 +186,7 @@ impl MenuWindow {
                             if info.0.is_some() {
                                 menu!("Appearance", color, AppearanceWindow);
                             }
-                            //menu!("Text", text, TextWindow);
+                            menu!("Text", text, TextWindow);
                             if info.4.is_some() {
                                 menu!("Material", material, MaterialWindow);
                             }
@@ -173,6 +199,9 @@ impl MenuWindow {
                             if info.3.is_some() {
                                 menu!("Laser pens", lasermenu, LaserWindow);
                             }
+                            if info.6.is_some() {
+                                menu!("Lens", /, LensWindow);
+                            }
                             menu!("Information", info, InformationWindow);
                             if info.2.is_some() {
                                 menu!("Collision layers", collisions, CollisionsWindow);
@@ -182,6 +211,9 @@ impl MenuWindow {
                             }
                             menu!("Combine shapes", csg, CombineShapesWindow);
                             menu!("Controller", controller, ControllerWindow);
+                            if info.4.is_some() {
+                                menu!("Light sensor", /, LightSensorWindow);
+                            }
                             menu!("Script menu", /, ScriptMenuWindow);
                         }
                         None => {
@@ -194,7 +226,34 @@ impl MenuWindow {
                                 let scale = 1.0 / 182.0; // todo: depends on window size
                                 camera.scale = Vec3::new(scale, scale, 1.0);
                             }
+                            if item!("Add text", text) {
+                                add_obj.send(AddObjectEvent::Text(info_wnd.world_pos));
+                                commands.entity(wnd_id).despawn_recursive();
+                            }
+                            if item!("Add lens") {
+                                add_obj.send(AddObjectEvent::Lens {
+                                    pos: info_wnd.world_pos,
+                                    kind: LensKind::Biconvex,
+                                });
+                                commands.entity(wnd_id).despawn_recursive();
+                            }
+                            if item!("Add point light") {
+                                add_obj.send(AddObjectEvent::Laser(info_wnd.world_pos, Emitter::POINT_LIGHT));
+                                commands.entity(wnd_id).despawn_recursive();
+                            }
+                            if item!("Add parallel beam") {
+                                add_obj.send(AddObjectEvent::Laser(info_wnd.world_pos, Emitter::PARALLEL_BEAM));
+                                commands.entity(wnd_id).despawn_recursive();
+                            }
+                            if item!("Add curved mirror") {
+                                add_obj.send(AddObjectEvent::Lens {
+                                    pos: info_wnd.world_pos,
+                                    kind: LensKind::ConcaveMirror,
+                                });
+                                commands.entity(wnd_id).despawn_recursive();
+                            }
                             menu!("Background", color, BackgroundWindow);
+                            menu!("Statistics", /, StatisticsWindow);
                         }
                     }
                 });
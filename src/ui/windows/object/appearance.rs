@@ -1,4 +1,6 @@
 use crate::objects::ColorComponent;
+use crate::palette::PaletteConfig;
+use crate::ui::vectors::VectorDisplay;
 use crate::ui::{InitialPos, Subwindow};
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts};
@@ -12,17 +14,21 @@ pub struct AppearanceWindow;
 impl AppearanceWindow {
     pub fn show(
         mut wnds: Query<(Entity, &Parent, &mut InitialPos), With<AppearanceWindow>>,
-        mut ents: Query<&mut ColorComponent>,
+        mut ents: Query<(&mut ColorComponent, Option<&VectorDisplay>)>,
+        palette: Res<PaletteConfig>,
         mut egui_ctx: EguiContexts,
         mut commands: Commands,
     ) {
         let ctx = egui_ctx.ctx_mut();
         for (id, parent, mut initial_pos) in wnds.iter_mut() {
-            let mut color = ents.get_mut(parent.get()).unwrap();
+            let (mut color, display) = ents.get_mut(parent.get()).unwrap();
+            let mut display = display
+                .copied()
+                .unwrap_or_else(|| palette.current_palette.object_appearance.into());
             egui::Window::new("Appearance")
                 .resizable(false)
                 .default_size(egui::Vec2::ZERO)
-                .subwindow(id, ctx, &mut initial_pos, &mut commands, |ui, _commands| {
+                .subwindow(id, ctx, &mut initial_pos, &mut commands, |ui, commands| {
                     let mut hsva = color.0;
                     if egui::color_picker::color_picker_hsva_2d(
                         ui,
@@ -31,6 +37,14 @@ impl AppearanceWindow {
                     ) {
                         color.0 = hsva;
                     }
+
+                    ui.separator();
+                    let mut changed = ui.checkbox(&mut display.forces, "Show forces").changed();
+                    changed |= ui.checkbox(&mut display.velocities, "Show velocity").changed();
+                    changed |= ui.checkbox(&mut display.momentums, "Show momentum").changed();
+                    if changed {
+                        commands.entity(parent.get()).insert(display);
+                    }
                 });
         }
     }
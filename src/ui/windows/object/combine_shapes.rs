@@ -1,7 +1,41 @@
-use bevy::prelude::Component;
+use crate::objects::csg::{CombineShapesEvent, CsgOperation};
+use crate::ui::{InitialPos, Subwindow};
+use bevy::prelude::{Commands, Component, Entity, EventWriter, Parent, Query, With};
+use bevy_egui::{egui, EguiContexts};
 use crate::systems;
 
+systems!(CombineShapesWindow::show);
+
 #[derive(Default, Component)]
 pub struct CombineShapesWindow;
 
-systems!();
\ No newline at end of file
+impl CombineShapesWindow {
+    pub fn show(
+        mut wnds: Query<(Entity, &Parent, &mut InitialPos), With<CombineShapesWindow>>,
+        mut egui_ctx: EguiContexts,
+        mut commands: Commands,
+        mut combine: EventWriter<CombineShapesEvent>,
+    ) {
+        let ctx = egui_ctx.ctx_mut();
+        for (id, parent, mut initial_pos) in wnds.iter_mut() {
+            egui::Window::new("Combine shapes")
+                .resizable(false)
+                .default_size(egui::Vec2::ZERO)
+                .subwindow(id, ctx, &mut initial_pos, &mut commands, |ui, _commands| {
+                    ui.label("Combine with overlapping objects:");
+                    for (operation, text) in [
+                        (CsgOperation::Union, "Union"),
+                        (CsgOperation::Subtract, "Subtract"),
+                        (CsgOperation::Intersect, "Intersect"),
+                    ] {
+                        if ui.button(text).clicked() {
+                            combine.send(CombineShapesEvent {
+                                entity: parent.get(),
+                                operation,
+                            });
+                        }
+                    }
+                });
+        }
+    }
+}
@@ -1,7 +1,102 @@
-use bevy::prelude::Component;
+use crate::objects::controller::{ControllerAction, ControllerComponent};
+use crate::ui::{InitialPos, Subwindow};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use strum::IntoEnumIterator;
 use crate::systems;
 
+systems!(ControllerWindow::show);
+
 #[derive(Default, Component)]
-pub struct ControllerWindow;
+pub struct ControllerWindow {
+    /// Action waiting for a key press
+    listening: Option<ControllerAction>,
+}
+
+impl ControllerWindow {
+    pub fn show(
+        mut wnds: Query<(Entity, &Parent, &mut InitialPos, &mut ControllerWindow)>,
+        mut ents: Query<&mut ControllerComponent>,
+        keys: Res<Input<KeyCode>>,
+        mut egui_ctx: EguiContexts,
+        mut commands: Commands,
+    ) {
+        let ctx = egui_ctx.ctx_mut();
+        for (id, parent, mut initial_pos, mut wnd) in wnds.iter_mut() {
+            let Ok(mut controller) = ents.get_mut(parent.get()) else {
+                commands.entity(parent.get()).insert(ControllerComponent::default());
+                continue;
+            };
+
+            if let Some(action) = wnd.listening {
+                if let Some(&key) = keys.get_just_pressed().next() {
+                    *controller.binding_mut(action) = match key {
+                        KeyCode::Escape => None,
+                        key => Some(key),
+                    };
+                    wnd.listening = None;
+                }
+            }
 
-systems!();
\ No newline at end of file
+            egui::Window::new("Controller")
+                .resizable(false)
+                .default_size(egui::Vec2::ZERO)
+                .subwindow(id, ctx, &mut initial_pos, &mut commands, |ui, _commands| {
+                    egui::Grid::new("controller grid").show(ui, |ui| {
+                        for action in ControllerAction::iter() {
+                            ui.label(<&'static str>::from(action));
+                            let text = if wnd.listening == Some(action) {
+                                "Press a key (Esc to clear)".to_string()
+                            } else {
+                                match controller.binding(action) {
+                                    Some(key) => format!("{:?}", key),
+                                    None => "None".to_string(),
+                                }
+                            };
+                            if ui
+                                .selectable_label(wnd.listening == Some(action), text)
+                                .clicked()
+                            {
+                                wnd.listening = Some(action);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                    ui.separator();
+                    ui.add(
+                        egui::Slider::new(&mut controller.thrust_force, 0.1..=10000.0)
+                            .logarithmic(true)
+                            .suffix("N")
+                            .text("Thrust force :")
+                            .custom(),
+                    );
+                    let mut angle = controller.thrust_angle.to_degrees();
+                    if ui
+                        .add(
+                            egui::Slider::new(&mut angle, -180.0..=180.0)
+                                .suffix("°")
+                                .text("Thrust angle :")
+                                .custom(),
+                        )
+                        .changed()
+                    {
+                        controller.thrust_angle = angle.to_radians();
+                    }
+                    ui.add(
+                        egui::Slider::new(&mut controller.jump_impulse, 0.1..=1000.0)
+                            .logarithmic(true)
+                            .suffix("Ns")
+                            .text("Jump impulse :")
+                            .custom(),
+                    );
+                    ui.add(
+                        egui::Slider::new(&mut controller.torque, 0.1..=10000.0)
+                            .logarithmic(true)
+                            .suffix("Nm")
+                            .text("Turning torque :")
+                            .custom(),
+                    );
+                });
+        }
+    }
+}
@@ -1,5 +1,5 @@
-use crate::objects::laser::LaserBundle;
-use crate::objects::SizeComponent;
+use crate::objects::laser::{Emitter, LaserBundle, Pulse, Spectrum, VISIBLE_RANGE};
+use crate::objects::{ColorComponent, SizeComponent};
 use crate::ui::{InitialPos, Subwindow};
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContexts};
@@ -13,17 +13,95 @@ pub struct LaserWindow;
 impl LaserWindow {
     pub fn show(
         mut wnds: Query<(Entity, &Parent, &mut InitialPos), With<LaserWindow>>,
-        mut ents: Query<(&mut LaserBundle, &mut SizeComponent)>,
+        mut ents: Query<(&mut LaserBundle, &mut SizeComponent, &ColorComponent)>,
         mut egui_ctx: EguiContexts,
         mut commands: Commands,
     ) {
         let ctx = egui_ctx.ctx_mut();
         for (id, parent, mut initial_pos) in wnds.iter_mut() {
-            let (mut laser, mut size) = ents.get_mut(parent.get()).unwrap();
+            let (mut laser, mut size, pen_color) = ents.get_mut(parent.get()).unwrap();
             egui::Window::new("Laser pens")
                 .resizable(false)
                 .default_size(egui::Vec2::ZERO)
                 .subwindow(id, ctx, &mut initial_pos, &mut commands, |ui, _commands| {
+                    ui.checkbox(&mut laser.enabled, "On");
+
+                    let emitter_name: &'static str = laser.emitter.into();
+                    egui::ComboBox::from_label("Emitter")
+                        .selected_text(emitter_name)
+                        .show_ui(ui, |ui| {
+                            for emitter in Emitter::KINDS {
+                                let name: &'static str = emitter.into();
+                                let selected = std::mem::discriminant(&laser.emitter)
+                                    == std::mem::discriminant(&emitter);
+                                if ui.selectable_label(selected, name).clicked() && !selected {
+                                    laser.emitter = emitter;
+                                }
+                            }
+                        });
+                    match &mut laser.emitter {
+                        Emitter::Pen => {}
+                        Emitter::Point { rays, angle } => {
+                            ui.add(egui::Slider::new(rays, 1..=128).text("Rays :").custom());
+                            let mut degrees = angle.to_degrees();
+                            if ui
+                                .add(
+                                    egui::Slider::new(&mut degrees, 1.0..=360.0)
+                                        .suffix("°")
+                                        .text("Angle :")
+                                        .custom(),
+                                )
+                                .changed()
+                            {
+                                *angle = degrees.to_radians();
+                            }
+                        }
+                        Emitter::Parallel { rays, width } => {
+                            ui.add(egui::Slider::new(rays, 1..=128).text("Rays :").custom());
+                            ui.add(
+                                egui::Slider::new(width, 0.01..=10.0)
+                                    .logarithmic(true)
+                                    .suffix("m")
+                                    .text("Width :")
+                                    .custom(),
+                            );
+                        }
+                    }
+                    ui.add(
+                        egui::Slider::new(&mut laser.max_rays, 10..=100_000)
+                            .logarithmic(true)
+                            .text("Max rays :")
+                            .custom(),
+                    );
+
+                    ui.add(
+                        egui::Slider::new(&mut laser.power, 1e-4..=1e4)
+                            .logarithmic(true)
+                            .suffix("W")
+                            .text("Power :")
+                            .custom(),
+                    );
+                    if laser.emitter == Emitter::Pen {
+                        ui.add(
+                            egui::Slider::new(&mut laser.width, 0.001..=1.0)
+                                .logarithmic(true)
+                                .suffix("m")
+                                .text("Beam width :")
+                                .custom(),
+                        );
+                    }
+                    let mut divergence = laser.divergence.to_degrees();
+                    if !matches!(laser.emitter, Emitter::Point { .. }) && ui
+                        .add(
+                            egui::Slider::new(&mut divergence, 0.0..=45.0)
+                                .suffix("°")
+                                .text("Divergence :")
+                                .custom(),
+                        )
+                        .changed()
+                    {
+                        laser.divergence = divergence.to_radians();
+                    }
                     ui.add(
                         egui::Slider::new(&mut laser.fade_distance, 1.0..=1000.0)
                             .logarithmic(true)
@@ -38,6 +116,66 @@ impl LaserWindow {
                             .text("Size :")
                             .custom(),
                     );
+
+                    ui.horizontal(|ui| {
+                        let mut white = laser.spectrum == Spectrum::White;
+                        if ui.checkbox(&mut white, "White light").changed() {
+                            laser.spectrum = if white {
+                                Spectrum::White
+                            } else {
+                                Spectrum::Monochromatic(532.0)
+                            };
+                        }
+                        let color = laser.spectrum.color();
+                        let [r, g, b, _] = color.to_srgba_unmultiplied();
+                        let (rect, _) = ui.allocate_exact_size(egui::vec2(16.0, 16.0), egui::Sense::hover());
+                        ui.painter().rect_filled(rect, 2.0, egui::Color32::from_rgb(r, g, b));
+                        if ui.button("Use pen color").clicked() {
+                            laser.spectrum = Spectrum::from_color(pen_color.0);
+                        }
+                    });
+
+                    if let Spectrum::Monochromatic(wavelength) = &mut laser.spectrum {
+                        ui.add(
+                            egui::Slider::new(wavelength, VISIBLE_RANGE)
+                                .suffix("nm")
+                                .text("Wavelength :")
+                                .custom(),
+                        );
+                    }
+
+                    ui.separator();
+
+                    ui.checkbox(&mut laser.radiation_pressure, "Radiation pressure");
+                    if laser.radiation_pressure {
+                        ui.add(
+                            egui::Slider::new(&mut laser.pressure_factor, 1.0..=1e12)
+                                .logarithmic(true)
+                                .text("Exaggeration :")
+                                .custom(),
+                        );
+                    }
+
+                    ui.separator();
+
+                    let mut pulsed = laser.pulse.is_some();
+                    if ui.checkbox(&mut pulsed, "Pulsed").changed() {
+                        laser.pulse = pulsed.then(Pulse::default);
+                    }
+                    if let Some(pulse) = &mut laser.pulse {
+                        ui.add(
+                            egui::Slider::new(&mut pulse.period, 0.01..=60.0)
+                                .logarithmic(true)
+                                .suffix("s")
+                                .text("Period :")
+                                .custom(),
+                        );
+                        ui.add(
+                            egui::Slider::new(&mut pulse.duty_cycle, 0.0..=1.0)
+                                .text("Duty cycle :")
+                                .custom(),
+                        );
+                    }
                 });
         }
     }
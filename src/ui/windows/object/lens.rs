@@ -0,0 +1,125 @@
+use crate::objects::lens::{Lens, LensKind};
+use crate::objects::phy_obj::{OpticalMaterial, RefractiveIndex, REFERENCE_WAVELENGTH};
+use crate::ui::{InitialPos, Subwindow};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use strum::IntoEnumIterator;
+use crate::systems;
+
+systems!(LensWindow::show);
+
+#[derive(Default, Component)]
+pub struct LensWindow;
+
+/// Edits a radius of curvature, flat surfaces being infinite.
+fn radius_editor(ui: &mut egui::Ui, lens: &Lens, radius: &mut f32, text: &str) {
+    ui.horizontal(|ui| {
+        ui.label(text);
+        if radius.is_finite() {
+            ui.add(egui::DragValue::new(radius).speed(0.01).suffix(" m"));
+            *radius = lens.clamp_radius(*radius);
+        } else {
+            ui.label("flat");
+        }
+    });
+}
+
+impl LensWindow {
+    pub fn show(
+        mut wnds: Query<(Entity, &Parent, &mut InitialPos), With<LensWindow>>,
+        mut ents: Query<(&mut Lens, &RefractiveIndex, &mut OpticalMaterial)>,
+        mut egui_ctx: EguiContexts,
+        mut commands: Commands,
+    ) {
+        let ctx = egui_ctx.ctx_mut();
+        for (id, parent, mut initial_pos) in wnds.iter_mut() {
+            let Ok((mut lens_obj, index, mut material)) = ents.get_mut(parent.get()) else { continue };
+            // only touch the component when something changed, the collider is rebuilt then
+            let mut lens = *lens_obj;
+            let index = index.at(REFERENCE_WAVELENGTH);
+            let focal_length = lens.focal_length(index);
+
+            egui::Window::new("Lens")
+                .resizable(false)
+                .default_size(egui::Vec2::ZERO)
+                .subwindow(id, ctx, &mut initial_pos, &mut commands, |ui, _commands| {
+                    let kind_name: &'static str = lens.kind.into();
+                    egui::ComboBox::from_label("Kind")
+                        .selected_text(kind_name)
+                        .show_ui(ui, |ui| {
+                            for kind in LensKind::iter() {
+                                let name: &'static str = kind.into();
+                                ui.selectable_value(&mut lens.kind, kind, name);
+                            }
+                        });
+                    if lens.kind != lens_obj.kind {
+                        lens.set_focal_length(focal_length, index);
+                        if lens.kind.is_mirror() != lens_obj.kind.is_mirror() {
+                            material.reflectivity = lens.kind.material().reflectivity;
+                        }
+                    }
+
+                    let mut focal = focal_length.abs();
+                    if ui
+                        .add(
+                            egui::Slider::new(&mut focal, 0.05..=50.0)
+                                .logarithmic(true)
+                                .suffix("m")
+                                .text("Focal length :")
+                                .custom(),
+                        )
+                        .changed()
+                    {
+                        lens.set_focal_length(focal, index);
+                    }
+
+                    if lens.kind.is_mirror() {
+                        let mut radius = lens.front;
+                        radius_editor(ui, &lens, &mut radius, "Radius :");
+                        lens.front = radius;
+                        lens.back = radius;
+                    } else {
+                        let (mut front, mut back) = (lens.front, lens.back);
+                        radius_editor(ui, &lens, &mut front, "Left radius :");
+                        radius_editor(ui, &lens, &mut back, "Right radius :");
+                        lens.front = front;
+                        lens.back = back;
+                    }
+
+                    ui.add(
+                        egui::Slider::new(&mut lens.thickness, 0.01..=1.0)
+                            .logarithmic(true)
+                            .suffix("m")
+                            .text("Thickness :")
+                            .custom(),
+                    );
+                    if ui
+                        .add(
+                            egui::Slider::new(&mut lens.aperture, 0.1..=10.0)
+                                .logarithmic(true)
+                                .suffix("m")
+                                .text("Aperture :")
+                                .custom(),
+                        )
+                        .changed()
+                    {
+                        lens.front = lens.clamp_radius(lens.front);
+                        lens.back = lens.clamp_radius(lens.back);
+                    }
+
+                    ui.separator();
+
+                    ui.label(format!(
+                        "f = {:.3} m (n = {:.4})",
+                        lens.focal_length(index),
+                        index
+                    ));
+                    ui.checkbox(&mut lens.show_focal_points, "Show focal points");
+                });
+
+            if lens != *lens_obj {
+                *lens_obj = lens;
+            }
+        }
+    }
+}
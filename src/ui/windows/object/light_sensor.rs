@@ -0,0 +1,59 @@
+use crate::objects::light_sensor::LightSensor;
+use crate::ui::{InitialPos, Subwindow};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use crate::systems;
+
+systems!(LightSensorWindow::show);
+
+#[derive(Default, Component)]
+pub struct LightSensorWindow;
+
+impl LightSensorWindow {
+    pub fn show(
+        mut wnds: Query<(Entity, &Parent, &mut InitialPos), With<LightSensorWindow>>,
+        mut ents: Query<&mut LightSensor>,
+        mut egui_ctx: EguiContexts,
+        mut commands: Commands,
+    ) {
+        let ctx = egui_ctx.ctx_mut();
+        for (id, parent, mut initial_pos) in wnds.iter_mut() {
+            let Ok(mut sensor) = ents.get_mut(parent.get()) else {
+                commands.entity(parent.get()).insert(LightSensor::default());
+                continue;
+            };
+
+            egui::Window::new("Light sensor")
+                .resizable(false)
+                .default_size(egui::Vec2::ZERO)
+                .subwindow(id, ctx, &mut initial_pos, &mut commands, |ui, commands| {
+                    ui.add(
+                        egui::Slider::new(&mut sensor.threshold, 1e-6..=100.0)
+                            .logarithmic(true)
+                            .suffix("W")
+                            .text("Threshold :")
+                            .custom(),
+                    );
+                    ui.checkbox(&mut sensor.drive_motors, "Run the axles' motors when lit");
+
+                    ui.separator();
+
+                    ui.horizontal(|ui| {
+                        ui.label(format!("Power: {:.3e} W", sensor.power));
+                        let [r, g, b, _] = sensor.color.to_srgba_unmultiplied();
+                        let (rect, _) = ui.allocate_exact_size(egui::vec2(16.0, 16.0), egui::Sense::hover());
+                        ui.painter().rect_filled(rect, 2.0, egui::Color32::from_rgb(r, g, b));
+                    });
+                    if let Some(wavelength) = sensor.wavelength {
+                        ui.label(format!("Wavelength: {:.0} nm", wavelength));
+                    }
+                    ui.label(if sensor.lit { "Lit" } else { "Dark" });
+
+                    if ui.button("Remove sensor").clicked() {
+                        commands.entity(parent.get()).remove::<LightSensor>();
+                        commands.entity(id).despawn_recursive();
+                    }
+                });
+        }
+    }
+}
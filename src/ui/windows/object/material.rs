@@ -1,6 +1,8 @@
-use crate::objects::phy_obj::RefractiveIndex;
+use crate::objects::phy_obj::{OpticalMaterial, RefractiveIndex, REFERENCE_WAVELENGTH};
 use crate::ui::{InitialPos, Subwindow};
 use bevy::prelude::{Commands, Component, Entity, Parent, Query, With};
+use bevy_egui::egui::color_picker::Alpha;
+use bevy_egui::egui::ecolor::Hsva;
 use bevy_egui::{egui, EguiContexts};
 use bevy_rapier2d::prelude::{Friction, Restitution};
 use crate::systems;
@@ -10,16 +12,87 @@ systems!(MaterialWindow::show);
 #[derive(Default, Component)]
 pub struct MaterialWindow;
 
+/// Borosilicate crown glass
+const SELLMEIER_BK7: RefractiveIndex = RefractiveIndex::Sellmeier {
+    b: [1.039_612_1, 0.231_792_34, 1.010_469_5],
+    c: [0.006_000_699, 0.020_017_914, 103.560_65],
+};
+
+const CLEAR: Option<Hsva> = Some(Hsva {
+    h: 0.0,
+    s: 0.0,
+    v: 1.0,
+    a: 0.0,
+});
+
+const TRANSPARENT: OpticalMaterial = OpticalMaterial {
+    reflectivity: 0.0,
+    absorption: 0.0,
+    diffuse: 0.0,
+    filter: CLEAR,
+};
+
+/// Name, refractive index (kept as is if `None`) and optical properties
+const PRESETS: [(&str, Option<RefractiveIndex>, OpticalMaterial); 5] = [
+    (
+        "Mirror",
+        None,
+        OpticalMaterial {
+            reflectivity: 1.0,
+            ..TRANSPARENT
+        },
+    ),
+    ("Glass", Some(SELLMEIER_BK7), TRANSPARENT),
+    (
+        "Water",
+        Some(RefractiveIndex::Cauchy {
+            a: 1.3242,
+            b: 0.00306,
+        }),
+        OpticalMaterial {
+            absorption: 0.05,
+            ..TRANSPARENT
+        },
+    ),
+    (
+        "Diamond",
+        Some(RefractiveIndex::Sellmeier {
+            b: [0.3306, 4.3356, 0.0],
+            c: [0.030625, 0.011236, 0.0],
+        }),
+        TRANSPARENT,
+    ),
+    (
+        "Black",
+        // no interface, so nothing is reflected
+        Some(RefractiveIndex::VACUUM),
+        OpticalMaterial {
+            filter: Some(Hsva {
+                h: 0.0,
+                s: 0.0,
+                v: 0.0,
+                a: 1.0,
+            }),
+            ..TRANSPARENT
+        },
+    ),
+];
+
 impl MaterialWindow {
     pub fn show(
         mut wnds: Query<(Entity, &Parent, &mut InitialPos), With<MaterialWindow>>,
-        mut ents: Query<(&mut Restitution, &mut RefractiveIndex, &mut Friction)>,
+        mut ents: Query<(
+            &mut Restitution,
+            &mut RefractiveIndex,
+            &mut OpticalMaterial,
+            &mut Friction,
+        )>,
         mut egui_ctx: EguiContexts,
         mut commands: Commands,
     ) {
         let ctx = egui_ctx.ctx_mut();
         for (id, parent, mut initial_pos) in wnds.iter_mut() {
-            let (mut restitution, mut refractive, mut friction) =
+            let (mut restitution, mut refractive, mut material, mut friction) =
                 ents.get_mut(parent.get()).unwrap();
             egui::Window::new("Material")
                 .resizable(false)
@@ -37,13 +110,122 @@ impl MaterialWindow {
                             .custom(),
                     );
 
+                    ui.separator();
+
+                    ui.horizontal_wrapped(|ui| {
+                        ui.label("Presets :");
+                        for (name, index, preset) in PRESETS {
+                            if ui.button(name).clicked() {
+                                if let Some(index) = index {
+                                    *refractive = index;
+                                }
+                                *material = preset;
+                            }
+                        }
+                    });
+
+                    let nominal = refractive.nominal();
+                    let model = match *refractive {
+                        RefractiveIndex::Constant(_) => "Constant",
+                        RefractiveIndex::Cauchy { .. } => "Cauchy",
+                        RefractiveIndex::Sellmeier { .. } => "Sellmeier",
+                    };
+                    egui::ComboBox::from_label("Dispersion :")
+                        .selected_text(model)
+                        .show_ui(ui, |ui| {
+                            if ui.selectable_label(model == "Constant", "Constant").clicked() {
+                                *refractive = RefractiveIndex::Constant(nominal);
+                            }
+                            if ui.selectable_label(model == "Cauchy", "Cauchy").clicked() {
+                                *refractive = if nominal.is_finite() {
+                                    RefractiveIndex::Cauchy { a: nominal, b: 0.0 }
+                                } else {
+                                    RefractiveIndex::default()
+                                };
+                            }
+                            if ui.selectable_label(model == "Sellmeier", "Sellmeier").clicked() {
+                                *refractive = SELLMEIER_BK7;
+                            }
+                        });
+
+                    match &mut *refractive {
+                        RefractiveIndex::Constant(n) => {
+                            ui.add(
+                                egui::Slider::new(n, 1.0..=f32::INFINITY)
+                                    .logarithmic(true)
+                                    .largest_finite(100.0)
+                                    .text("Refractive index :")
+                                    .custom(),
+                            );
+                        }
+                        RefractiveIndex::Cauchy { a, b } => {
+                            ui.add(egui::Slider::new(a, 1.0..=3.0).text("A :").custom());
+                            ui.add(
+                                egui::Slider::new(b, 0.0..=0.1)
+                                    .suffix("µm²")
+                                    .text("B :")
+                                    .custom(),
+                            );
+                        }
+                        RefractiveIndex::Sellmeier { b, c } => {
+                            egui::Grid::new((id, "sellmeier")).show(ui, |ui| {
+                                for (i, (b, c)) in b.iter_mut().zip(c.iter_mut()).enumerate() {
+                                    ui.label(format!("B{} :", i + 1));
+                                    ui.add(egui::DragValue::new(b).speed(0.001));
+                                    ui.label(format!("C{} :", i + 1));
+                                    ui.add(
+                                        egui::DragValue::new(c)
+                                            .speed(0.001)
+                                            .suffix("µm²"),
+                                    );
+                                    ui.end_row();
+                                }
+                            });
+                        }
+                    }
+
+                    if refractive.is_dispersive() {
+                        ui.label(format!(
+                            "n = {:.4} at {} nm, Abbe number: {:.1}",
+                            refractive.nominal(),
+                            REFERENCE_WAVELENGTH,
+                            refractive.abbe_number()
+                        ));
+                    }
+
+                    ui.add(
+                        egui::Slider::new(&mut material.reflectivity, 0.0..=1.0)
+                            .text("Reflectivity :")
+                            .custom(),
+                    );
+
                     ui.add(
-                        egui::Slider::new(&mut refractive.0, 1.0..=f32::INFINITY)
+                        egui::Slider::new(&mut material.absorption, 0.0..=100.0)
                             .logarithmic(true)
-                            .largest_finite(100.0)
-                            .text("Refractive index :")
+                            .suffix("/m")
+                            .text("Absorption :")
                             .custom(),
                     );
+
+                    ui.add(
+                        egui::Slider::new(&mut material.diffuse, 0.0..=1.0)
+                            .text("Diffuse :")
+                            .custom(),
+                    );
+
+                    ui.horizontal(|ui| {
+                        let mut filtered = material.filter.is_some();
+                        if ui.checkbox(&mut filtered, "Color filter").changed() {
+                            material.filter = if filtered {
+                                Some(Hsva::new(0.0, 0.0, 1.0, 1.0))
+                            } else {
+                                None
+                            };
+                        }
+                        if let Some(filter) = &mut material.filter {
+                            egui::color_picker::color_edit_button_hsva(ui, filter, Alpha::OnlyBlend);
+                        }
+                    });
                 });
         }
     }
@@ -11,6 +11,8 @@ systems! {
     mod hinge,
     mod information,
     mod laser,
+    mod lens,
+    mod light_sensor,
     mod material,
     mod plot,
     mod script,
@@ -1,22 +1,32 @@
-use crate::measures::{GravityEnergy, KineticEnergy, Momentum};
+use crate::measures::{
+    Acceleration, Contacts, Forces, GravityEnergy, KineticEnergy, Momentum, SimulationTime,
+};
+use crate::objects::light_sensor::LightSensor;
 use crate::ui::images::GuiIcons;
-use crate::ui::{InitialPos, Subwindow};
+use crate::ui::{InitialPos, Subwindow, UiState};
+use crate::ToRot;
+use bevy::math::Vec3Swizzles;
 use bevy::hierarchy::Parent;
-use bevy::prelude::{Commands, Component, Entity, Query, Res, Time, Transform};
-use bevy_egui::egui::plot::{Line, Plot, PlotPoint, PlotPoints};
+use bevy::log::{error, info};
+use bevy::prelude::{Commands, Component, Entity, Query, Res, Transform};
+use bevy_egui::egui::plot::{Line, LineStyle, Plot, PlotPoint, PlotPoints, Polygon, VLine};
 use bevy_egui::{egui, EguiContexts};
 use bevy_rapier2d::dynamics::Velocity;
-use bevy_rapier2d::plugin::RapierConfiguration;
 use itertools::Itertools;
 use paste::paste;
+use serde::Serialize;
 use std::borrow::Borrow;
 use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Display, Formatter};
 use std::hash::{Hash, Hasher};
 use crate::systems;
+use fit::FitModel;
+use strum::IntoEnumIterator;
 
 systems!(PlotWindow::show);
 
+mod fit;
+
 #[derive(Component)]
 pub struct PlotWindow {
     series: HashMap<PlotSeriesId, PlotSeries>,
@@ -24,23 +34,85 @@ pub struct PlotWindow {
     measures_x: HashSet<&'static PlotQuantity>,
     category_y: &'static [PlotQuantity],
     measures_y: HashSet<&'static PlotQuantity>,
-    time: f32,
+    sources: Vec<PlotSource>,
+    /// Body relative quantities are measured against
+    reference: Option<Entity>,
+    /// Older points get decimated past this count
+    max_points: usize,
+    /// Physics step of the last sample
+    last_step: u64,
+    analysis: Analysis,
+    /// Outcome of the last export, shown until dismissed
+    export_status: Option<String>,
+}
+
+struct Analysis {
+    series: Option<String>,
+    model: FitModel,
+    /// X range the analysis is restricted to
+    range: Option<(f64, f64)>,
+    /// X range currently shown by the plot
+    visible: (f64, f64),
+    /// Where a drag selecting the range started
+    selecting: Option<f64>,
+}
+
+impl Default for Analysis {
+    fn default() -> Self {
+        Self {
+            series: None,
+            model: FitModel::Linear,
+            range: None,
+            visible: (0.0, 1.0),
+            selecting: None,
+        }
+    }
+}
+
+/// Bodies a series is measured on
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum PlotSource {
+    /// Object the window belongs to
+    This,
+    Body(Entity),
+    /// Sum over the objects added to the plot
+    Plotted,
+    /// Sum over every object of the scene
+    Scene,
+}
+
+impl PlotSource {
+    fn suffix(&self) -> String {
+        match self {
+            PlotSource::This => String::new(),
+            PlotSource::Body(ent) => format!(" [#{}]", ent.index()),
+            PlotSource::Plotted => " [total]".to_string(),
+            PlotSource::Scene => " [scene]".to_string(),
+        }
+    }
 }
 
 struct PlotSeriesId {
     name: String,
     x: &'static PlotQuantity,
     y: &'static PlotQuantity,
+    source: PlotSource,
 }
 
 impl PlotSeriesId {
-    fn new(x: &'static PlotQuantity, y: &'static PlotQuantity) -> Self {
+    fn new(x: &'static PlotQuantity, y: &'static PlotQuantity, source: PlotSource) -> Self {
         Self {
-            name: format!("{} / {}", y.name, x.name),
+            name: format!("{} / {}{}", y.name, x.name, source.suffix()),
             x,
             y,
+            source,
         }
     }
+
+    /// Header of the exported column holding one of the axes, with its unit and source
+    fn column(&self, quantity: &PlotQuantity) -> String {
+        format!("{}{}", quantity.label(), self.source.suffix())
+    }
 }
 
 impl Hash for PlotSeriesId {
@@ -51,7 +123,7 @@ impl Hash for PlotSeriesId {
 
 impl PartialEq for PlotSeriesId {
     fn eq(&self, other: &Self) -> bool {
-        std::ptr::eq(self.x, other.x) && std::ptr::eq(self.y, other.y)
+        std::ptr::eq(self.x, other.x) && std::ptr::eq(self.y, other.y) && self.source == other.source
     }
 }
 
@@ -79,24 +151,173 @@ struct PlotSeries {
     values: Vec<PlotPoint>,
 }
 
+#[derive(Serialize)]
+struct ExportedAxis {
+    name: &'static str,
+    unit: &'static str,
+}
+
+impl From<&PlotQuantity> for ExportedAxis {
+    fn from(quantity: &PlotQuantity) -> Self {
+        Self {
+            name: quantity.name,
+            unit: quantity.unit,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ExportedSeries<'a> {
+    name: &'a str,
+    x: ExportedAxis,
+    y: ExportedAxis,
+    points: Vec<[f64; 2]>,
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Tooltip of a point of the plot. Lines that aren't series, like the fitted curve, only get
+/// their coordinates.
+fn hover_label(series: &HashMap<PlotSeriesId, PlotSeries>, name: &str, value: &PlotPoint) -> String {
+    if name.is_empty() {
+        return String::new();
+    }
+    let Some((id, series)) = series.get_key_value(name) else {
+        return format!("{}\nx = {:.2}\ny = {:.2}", name, value.x, value.y);
+    };
+    let mut base = format!("x = {:.2} ({})\ny = {:.2} ({})", value.x, id.x, value.y, id.y);
+    let values = &series.values;
+    let idx = values.binary_search_by(|probe| probe.x.total_cmp(&value.x));
+    if let Ok(idx) = idx {
+        if idx > 5 {
+            let prev = &values[idx - 5];
+            let slope = (value.y - prev.y) / (value.x - prev.x);
+            base += &format!("\ndy/dx = {:.2}", slope);
+        }
+
+        let integ = values.windows(2).take(idx).map(|w| (w[0].y + w[1].y) * (w[1].x - w[0].x) / 2.0).sum::<f64>();
+        base += &format!("\n∫dt = {:.2}", integ);
+    }
+    base
+}
+
+/// Points drawn per series, longer series are strided through
+const MAX_DRAWN_POINTS: usize = 2000;
+
 impl PlotSeries {
     fn new() -> Self {
         Self { values: Vec::new() }
     }
+
+    /// Keeps the series under `max` points by halving the resolution of its older half.
+    fn push(&mut self, point: PlotPoint, max: usize) {
+        self.values.push(point);
+        if self.values.len() > max {
+            let half = self.values.len() / 2;
+            let mut i = 0;
+            self.values.retain(|_| {
+                i += 1;
+                i > half || i % 2 == 1
+            });
+        }
+    }
+
+    fn drawn_points(&self) -> PlotPoints {
+        let stride = (self.values.len() / MAX_DRAWN_POINTS).max(1);
+        let mut points = self.values.iter().step_by(stride).copied().collect_vec();
+        if let Some(&last) = self.values.last() {
+            if points.last().map_or(true, |p| p.x != last.x) {
+                points.push(last);
+            }
+        }
+        PlotPoints::Owned(points)
+    }
 }
 
-type PlotQuery<'a> = (
+pub(crate) type PlotQuery<'a> = (
     &'a Transform,
     &'a Velocity,
     &'a KineticEnergy,
     &'a GravityEnergy,
     &'a Momentum,
+    &'a Contacts,
+    &'a Acceleration,
+    &'a Forces,
+    Option<&'a LightSensor>,
 );
-type QuantityFn = fn(f32, PlotQuery) -> f32;
+pub(crate) type QuantityFn = fn(f32, PlotQuery) -> f32;
+/// Measure of a body against a reference body
+pub(crate) type RelativeFn = fn(PlotQuery, PlotQuery) -> f32;
 
-struct PlotQuantity {
-    name: &'static str,
-    measure: QuantityFn,
+#[derive(Copy, Clone)]
+pub(crate) enum Measure {
+    Body(QuantityFn),
+    Relative(RelativeFn),
+}
+
+/// How a quantity is computed over several bodies
+#[derive(Copy, Clone, PartialEq)]
+pub(crate) enum Aggregate {
+    /// Doesn't make sense for a group of bodies
+    None,
+    /// Same for every body
+    Shared,
+    Sum,
+}
+
+pub(crate) struct PlotQuantity {
+    pub name: &'static str,
+    pub unit: &'static str,
+    pub measure: Measure,
+    pub aggregate: Aggregate,
+}
+
+impl PlotQuantity {
+    const fn summed(self) -> Self {
+        Self {
+            aggregate: Aggregate::Sum,
+            ..self
+        }
+    }
+
+    const fn shared(self) -> Self {
+        Self {
+            aggregate: Aggregate::Shared,
+            ..self
+        }
+    }
+
+    /// Value for a single body, if the quantity doesn't need a reference
+    pub fn measure_body(&self, time: f32, body: PlotQuery) -> Option<f32> {
+        match self.measure {
+            Measure::Body(measure) => Some(measure(time, body)),
+            Measure::Relative(_) => None,
+        }
+    }
+
+    fn measure_total<'a>(&self, time: f32, mut bodies: impl Iterator<Item = PlotQuery<'a>>) -> Option<f32> {
+        let Measure::Body(measure) = self.measure else { return None };
+        match self.aggregate {
+            Aggregate::None => None,
+            Aggregate::Shared => bodies.next().map(|body| measure(time, body)),
+            Aggregate::Sum => Some(bodies.map(|body| measure(time, body)).sum()),
+        }
+    }
+
+    /// Name followed by the unit, for axis labels and exported columns
+    pub fn label(&self) -> String {
+        if self.unit.is_empty() {
+            self.name.to_string()
+        } else {
+            format!("{} ({})", self.name, self.unit)
+        }
+    }
 }
 
 impl Display for PlotQuantity {
@@ -107,51 +328,118 @@ impl Display for PlotQuantity {
 
 type PlotQuantityCategory = &'static [PlotQuantity];
 
-const fn quantity(name: &'static str, measure: QuantityFn) -> PlotQuantity {
-    PlotQuantity { name, measure }
+const fn quantity(name: &'static str, unit: &'static str, measure: QuantityFn) -> PlotQuantity {
+    PlotQuantity {
+        name,
+        unit,
+        measure: Measure::Body(measure),
+        aggregate: Aggregate::None,
+    }
+}
+
+const fn relative(name: &'static str, unit: &'static str, measure: RelativeFn) -> PlotQuantity {
+    PlotQuantity {
+        name,
+        unit,
+        measure: Measure::Relative(measure),
+        aggregate: Aggregate::None,
+    }
 }
 
-static PLOT_QUANTITIES: &[&[PlotQuantity]] = &[
-    &[quantity("Time", |time, _| time)],
+pub(crate) static PLOT_QUANTITIES: &[&[PlotQuantity]] = &[
+    &[quantity("Time", "s", |time, _| time).shared()],
+    &[
+        quantity("Position (x)", "m", |_, query| query.0.translation.x),
+        quantity("Position (y)", "m", |_, query| query.0.translation.y),
+    ],
+    &[
+        quantity("Speed", "m/s", |_, query| query.1.linvel.length()),
+        quantity("Velocity (x)", "m/s", |_, query| query.1.linvel.x),
+        quantity("Velocity (y)", "m/s", |_, query| query.1.linvel.y),
+    ],
+    &[quantity("Angular velocity", "rad/s", |_, query| query.1.angvel)],
+    &[
+        quantity("Acceleration", "m/s²", |_, query| query.6.linear.length()),
+        quantity("Acceleration (x)", "m/s²", |_, query| query.6.linear.x),
+        quantity("Acceleration (y)", "m/s²", |_, query| query.6.linear.y),
+    ],
+    &[quantity("Angular acceleration", "rad/s²", |_, query| query.6.angular)],
+    &[
+        quantity("Net force", "N", |_, query| query.7.net_force().length()),
+        quantity("Net force (x)", "N", |_, query| query.7.net_force().x).summed(),
+        quantity("Net force (y)", "N", |_, query| query.7.net_force().y).summed(),
+    ],
+    &[quantity("Net torque", "N·m", |_, query| query.7.net_torque()).summed()],
+    &[quantity("Power", "W", |_, query| {
+        query.7.net_force().dot(query.1.linvel) + query.7.net_torque() * query.1.angvel
+    })],
+    &[
+        quantity("Momentum (x)", "kg·m/s", |_, query| query.4.linear.x).summed(),
+        quantity("Momentum (y)", "kg·m/s", |_, query| query.4.linear.y).summed(),
+    ],
+    &[quantity("Angular momentum", "kg·m²/s", |_, query| query.4.angular).summed()],
     &[
-        quantity("Position (x)", |_, query| query.0.translation.x),
-        quantity("Position (y)", |_, query| query.0.translation.y),
+        quantity("Contact force", "N", |_, query| query.5.total_force().length()),
+        quantity("Contact force (x)", "N", |_, query| query.5.total_force().x).summed(),
+        quantity("Contact force (y)", "N", |_, query| query.5.total_force().y).summed(),
     ],
+    &[quantity("Number of contacts", "", |_, query| query.5.count() as f32).summed()],
     &[
-        quantity("Speed", |_, query| query.1.linvel.length()),
-        quantity("Velocity (x)", |_, query| query.1.linvel.x),
-        quantity("Velocity (y)", |_, query| query.1.linvel.y),
+        quantity("Light power", "W", |_, query| query.8.map_or(0.0, |s| s.power)).summed(),
+        quantity("Light wavelength", "nm", |_, query| {
+            query.8.and_then(|s| s.wavelength).unwrap_or(0.0)
+        }),
+        quantity("Light hue", "", |_, query| query.8.map_or(0.0, |s| s.color.h)),
     ],
-    &[quantity("Angular velocity", |_, query| query.1.angvel)],
-    // todo: acceleration
-    // todo: force
     &[
-        quantity("Momentum (x)", |_, query| query.4.linear.x),
-        quantity("Momentum (y)", |_, query| query.4.linear.y),
+        quantity("Linear kinetic energy", "J", |_, query| query.2.linear).summed(),
+        quantity("Angular kinetic energy", "J", |_, query| query.2.angular).summed(),
+        quantity("Kinetic energy (sum)", "J", |_, query| query.2.total()).summed(),
+        quantity("Potential gravitational energy", "J", |_, query| query.3.energy).summed(),
+        quantity("Potential energy (sum)", "J", |_, query| query.3.energy).summed(),
+        quantity("Energy (sum)", "J", |_, query| query.2.total() + query.3.energy).summed(),
     ],
-    &[quantity("Angular momentum", |_, query| query.4.angular)],
     &[
-        quantity("Linear kinetic energy", |_, query| query.2.linear),
-        quantity("Angular kinetic energy", |_, query| query.2.angular),
-        quantity("Kinetic energy (sum)", |_, query| query.2.total()),
-        quantity("Potential gravitational energy", |_, query| query.3.energy),
-        quantity("Potential energy (sum)", |_, query| query.3.energy),
-        quantity("Energy (sum)", |_, query| query.2.total() + query.3.energy),
+        relative("Distance to reference", "m", |a, b| {
+            a.0.translation.xy().distance(b.0.translation.xy())
+        }),
+        relative("Relative speed", "m/s", |a, b| (a.1.linvel - b.1.linvel).length()),
+        relative("Relative velocity (x)", "m/s", |a, b| a.1.linvel.x - b.1.linvel.x),
+        relative("Relative velocity (y)", "m/s", |a, b| a.1.linvel.y - b.1.linvel.y),
+        relative("Angle to reference", "rad", |a, b| {
+            let offset = b.0.translation.xy() - a.0.translation.xy();
+            offset.y.atan2(offset.x)
+        }),
+        relative("Relative rotation", "rad", |a, b| {
+            a.0.rotation.to_rot() - b.0.rotation.to_rot()
+        }),
     ],
 ];
 
+pub(crate) fn find_quantity(name: &str) -> Option<&'static PlotQuantity> {
+    PLOT_QUANTITIES
+        .iter()
+        .flat_map(|group| group.iter())
+        .find(|quantity| quantity.name == name)
+}
+
 impl Default for PlotWindow {
     fn default() -> Self {
         Self {
             series: HashMap::from([(
-                PlotSeriesId::new(&PLOT_QUANTITIES[0][0], &PLOT_QUANTITIES[2][0]),
+                PlotSeriesId::new(&PLOT_QUANTITIES[0][0], &PLOT_QUANTITIES[2][0], PlotSource::This),
                 PlotSeries::new(),
             )]),
             category_x: PLOT_QUANTITIES[0],
             measures_x: HashSet::from([&PLOT_QUANTITIES[0][0]]),
             category_y: PLOT_QUANTITIES[2],
             measures_y: HashSet::from([&PLOT_QUANTITIES[2][0]]),
-            time: 0.0,
+            sources: vec![PlotSource::This],
+            reference: None,
+            max_points: 10_000,
+            last_step: 0,
+            analysis: Analysis::default(),
+            export_status: None,
         }
     }
 }
@@ -171,52 +459,273 @@ impl PartialEq for &'static PlotQuantity {
 impl Eq for &'static PlotQuantity {}
 
 impl PlotWindow {
+    /// Creates the series for every combination of measures and sources, removes the others
+    fn sync_series(&mut self) {
+        let wanted = self
+            .sources
+            .iter()
+            .flat_map(|&source| {
+                self.measures_x.iter().flat_map(move |&x| {
+                    self.measures_y
+                        .iter()
+                        .map(move |&y| PlotSeriesId::new(x, y, source))
+                })
+            })
+            .collect_vec();
+        self.series.retain(|id, _| wanted.contains(id));
+        for id in wanted {
+            self.series.entry(id).or_insert_with(PlotSeries::new);
+        }
+    }
+
+    fn plotted_bodies(&self, parent: Entity) -> impl Iterator<Item = Entity> + '_ {
+        self.sources.iter().filter_map(move |source| match *source {
+            PlotSource::This => Some(parent),
+            PlotSource::Body(ent) => Some(ent),
+            PlotSource::Plotted | PlotSource::Scene => None,
+        })
+    }
+
+    fn sample(
+        &self,
+        quantity: &PlotQuantity,
+        source: PlotSource,
+        parent: Entity,
+        time: f32,
+        ents: &Query<PlotQuery>,
+    ) -> Option<f64> {
+        let single = |ent: Entity| {
+            let body = ents.get(ent).ok()?;
+            match quantity.measure {
+                Measure::Body(measure) => Some(measure(time, body)),
+                Measure::Relative(measure) => Some(measure(body, ents.get(self.reference?).ok()?)),
+            }
+        };
+        let value = match source {
+            PlotSource::This => single(parent),
+            PlotSource::Body(ent) => single(ent),
+            PlotSource::Plotted => quantity.measure_total(
+                time,
+                self.plotted_bodies(parent).filter_map(|ent| ents.get(ent).ok()),
+            ),
+            PlotSource::Scene => quantity.measure_total(time, ents.iter()),
+        };
+        value.map(|value| value as f64)
+    }
+
+    /// Shows the statistics of the analyzed series, returns the fitted curve and its range.
+    fn show_analysis(&mut self, ui: &mut egui::Ui, id: Entity) -> Option<(fit::Fit, (f64, f64))> {
+        let names = self.series.keys().map(|id| id.name.clone()).sorted().collect_vec();
+        let analysis = &mut self.analysis;
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source((id, "analysis series"))
+                .selected_text(analysis.series.as_deref().unwrap_or("None"))
+                .show_ui(ui, |ui| {
+                    for name in names {
+                        ui.selectable_value(&mut analysis.series, Some(name.clone()), name);
+                    }
+                });
+            egui::ComboBox::from_id_source((id, "analysis model"))
+                .selected_text(<&'static str>::from(analysis.model))
+                .show_ui(ui, |ui| {
+                    for model in FitModel::iter() {
+                        ui.selectable_value(&mut analysis.model, model, <&'static str>::from(model));
+                    }
+                });
+        });
+        ui.horizontal(|ui| {
+            match &mut analysis.range {
+                Some((from, to)) => {
+                    ui.add(egui::DragValue::new(from).speed(0.01).prefix("From "));
+                    ui.add(egui::DragValue::new(to).speed(0.01).prefix("to "));
+                    if ui.button("Whole series").clicked() {
+                        analysis.range = None;
+                    }
+                }
+                None => {
+                    ui.label("Whole series");
+                }
+            }
+            if ui.button("Use visible range").clicked() {
+                analysis.range = Some(analysis.visible);
+            }
+        });
+        ui.weak("Shift-drag on the plot to select a range");
+
+        let series = self.series.get(analysis.series.as_deref()?)?;
+        let points = match analysis.range {
+            Some((from, to)) => series
+                .values
+                .iter()
+                .filter(|p| p.x >= from.min(to) && p.x <= from.max(to))
+                .copied()
+                .collect_vec(),
+            None => series.values.clone(),
+        };
+        let Some(stats) = fit::stats(&points) else {
+            ui.label("No data in range");
+            return None;
+        };
+        ui.label(format!(
+            "min = {:.4}, max = {:.4}, mean = {:.4}",
+            stats.min, stats.max, stats.mean
+        ));
+        match stats.period {
+            Some(period) => ui.label(format!("period = {:.4}", period)),
+            None => ui.label("no period detected"),
+        };
+        match fit::fit(analysis.model, &points) {
+            Some(fit) => {
+                ui.label(format!("{}\nR² = {:.5}", fit.describe(), fit.r_squared));
+                let from = points.first()?.x;
+                let to = points.last()?.x;
+                Some((fit, (from, to)))
+            }
+            None => {
+                ui.label("The model can't be fitted to this data");
+                None
+            }
+        }
+    }
+
+    fn sorted_series(&self) -> Vec<(&PlotSeriesId, &PlotSeries)> {
+        self.series
+            .iter()
+            .sorted_by(|a, b| a.0.name.cmp(&b.0.name))
+            .collect()
+    }
+
+    /// When all series share the time axis, they get a single time column and their values
+    /// are aligned on it. Otherwise, each series gets its own pair of columns.
+    fn to_csv(&self) -> String {
+        let series = self.sorted_series();
+        let time = &PLOT_QUANTITIES[0][0];
+        let mut rows: Vec<Vec<String>> = vec![];
+        if series.iter().all(|(id, _)| std::ptr::eq(id.x, time)) {
+            let mut header = vec![time.label()];
+            header.extend(series.iter().map(|(id, _)| id.column(id.y)));
+            rows.push(header);
+
+            let times = series
+                .iter()
+                .flat_map(|(_, s)| s.values.iter().map(|p| p.x))
+                .sorted_by(f64::total_cmp)
+                .dedup();
+            let mut cursors = vec![0; series.len()];
+            for t in times {
+                let mut row = vec![t.to_string()];
+                for ((_, s), cursor) in series.iter().zip(cursors.iter_mut()) {
+                    match s.values.get(*cursor) {
+                        Some(point) if point.x == t => {
+                            row.push(point.y.to_string());
+                            *cursor += 1;
+                        }
+                        _ => row.push(String::new()),
+                    }
+                }
+                rows.push(row);
+            }
+        } else {
+            rows.push(
+                series
+                    .iter()
+                    .flat_map(|(id, _)| [id.column(id.x), id.column(id.y)])
+                    .collect(),
+            );
+            let length = series.iter().map(|(_, s)| s.values.len()).max().unwrap_or(0);
+            for i in 0..length {
+                rows.push(
+                    series
+                        .iter()
+                        .flat_map(|(_, s)| match s.values.get(i) {
+                            Some(point) => [point.x.to_string(), point.y.to_string()],
+                            None => [String::new(), String::new()],
+                        })
+                        .collect(),
+                );
+            }
+        }
+        rows.iter()
+            .map(|row| row.iter().map(|field| csv_field(field)).join(","))
+            .join("\n")
+    }
+
+    fn to_json(&self) -> String {
+        let series: Vec<_> = self
+            .sorted_series()
+            .into_iter()
+            .map(|(id, s)| ExportedSeries {
+                name: &id.name,
+                x: id.x.into(),
+                y: id.y.into(),
+                points: s.values.iter().map(|p| [p.x, p.y]).collect(),
+            })
+            .collect();
+        serde_json::to_string_pretty(&series).expect("plot data is always serializable")
+    }
+
+    /// Writes the export in the working directory, returns the message shown in the window.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save(contents: String, extension: &str) -> String {
+        let stamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let name = format!("plot-{}.{}", stamp, extension);
+        let path = std::env::current_dir()
+            .map(|dir| dir.join(&name))
+            .unwrap_or_else(|_| name.into());
+        match std::fs::write(&path, contents) {
+            Ok(()) => {
+                info!("Plot exported to {}", path.display());
+                format!("Exported to {}", path.display())
+            }
+            Err(err) => {
+                error!("Couldn't export plot to {}: {}", path.display(), err);
+                format!("Couldn't export to {}: {}", path.display(), err)
+            }
+        }
+    }
+
     pub(crate) fn show(
         mut wnds: Query<(Entity, &Parent, &mut InitialPos, &mut PlotWindow)>,
         ents: Query<PlotQuery>,
         mut egui_ctx: EguiContexts,
         mut commands: Commands,
-        rapier_conf: Res<RapierConfiguration>,
-        time: Res<Time>,
+        sim: Res<SimulationTime>,
         gui_icons: Res<GuiIcons>,
+        ui_state: Res<UiState>,
     ) {
         let ctx = egui_ctx.ctx_mut();
+        let selected = ui_state
+            .selected_entity
+            .map(|sel| sel.entity)
+            .filter(|&ent| ents.contains(ent));
         for (id, parent, mut initial_pos, mut plot) in wnds.iter_mut() {
-            if rapier_conf.physics_pipeline_active {
-                let data = ents.get(parent.get()).unwrap();
-                let cur_time = plot.time;
-                for (name, series) in plot.series.iter_mut() {
-                    let x = (name.x.measure)(cur_time, data);
-                    let y = (name.y.measure)(cur_time, data);
-                    series.values.push(PlotPoint::new(x, y));
+            let parent = parent.get();
+            // several steps in a frame can't be told apart, the measures are only updated once
+            if sim.steps != plot.last_step {
+                let points = plot
+                    .series
+                    .keys()
+                    .map(|id| {
+                        let x = plot.sample(id.x, id.source, parent, sim.time, &ents);
+                        let y = plot.sample(id.y, id.source, parent, sim.time, &ents);
+                        x.zip(y)
+                    })
+                    .collect_vec();
+                let max_points = plot.max_points;
+                for (series, point) in plot.series.values_mut().zip(points) {
+                    if let Some((x, y)) = point {
+                        series.push(PlotPoint::new(x, y), max_points);
+                    }
                 }
-                plot.time += time.delta_seconds();
+                plot.last_step = sim.steps;
             }
             egui::Window::new("plot")
                 .resizable(true)
                 .subwindow(id, ctx, &mut initial_pos, &mut commands, |ui, _commands| {
                     let series = unsafe { &*(&plot.series as *const HashMap<PlotSeriesId, PlotSeries>) };
-                    let fmt = |name: &str, value: &PlotPoint| {
-                        if !name.is_empty() {
-                            let (id, series) = series.get_key_value(name).unwrap_or_else(|| panic!("series {} not found, available: {:?}", name, series.keys()));
-                            let mut base = format!("x = {:.2} ({})\ny = {:.2} ({})", value.x, id.x, value.y, id.y);
-                            let values = &series.values;
-                            let idx = values.binary_search_by(|probe| probe.x.total_cmp(&value.x));
-                            if let Ok(idx) = idx {
-                                if idx > 5 {
-                                    let prev = &values[idx - 5];
-                                    let slope = (value.y - prev.y) / (value.x - prev.x);
-                                    base += &format!("\ndy/dx = {:.2}", slope);
-                                }
-
-                                let integ = values.windows(2).take(idx).map(|w| (w[0].y + w[1].y) * (w[1].x - w[0].x) / 2.0).sum::<f64>();
-                                base += &format!("\n∫dt = {:.2}", integ);
-                            }
-                            base
-                        } else {
-                            String::from("")
-                        }
-                    };
+                    let fmt = |name: &str, value: &PlotPoint| hover_label(series, name, value);
                     ui.horizontal(|ui| {
                         if ui.add(egui::Button::image_and_text(gui_icons.plot_clear, [16.0, 16.0], "Clear"))
                             .clicked() {
@@ -225,6 +734,33 @@ impl PlotWindow {
                             }
                         }
 
+                        ui.add(
+                            egui::DragValue::new(&mut plot.max_points)
+                                .clamp_range(100..=1_000_000)
+                                .speed(100.0)
+                                .prefix("Max points: "),
+                        );
+
+                        ui.menu_button("Export", |ui| {
+                            #[cfg(not(target_arch = "wasm32"))]
+                            {
+                                if ui.button("Export CSV").clicked() {
+                                    plot.export_status = Some(Self::save(plot.to_csv(), "csv"));
+                                    ui.close_menu();
+                                }
+                                if ui.button("Export JSON").clicked() {
+                                    plot.export_status = Some(Self::save(plot.to_json(), "json"));
+                                    ui.close_menu();
+                                }
+                            }
+                            if ui.button("Copy to clipboard").clicked() {
+                                let csv = plot.to_csv();
+                                ui.output_mut(|o| o.copied_text = csv);
+                                plot.export_status = Some("Copied to clipboard".to_string());
+                                ui.close_menu();
+                            }
+                        });
+
                         macro_rules! axis {
                             ($name:literal, $sym:ident, $other:ident) => {
                                 paste! {
@@ -240,17 +776,12 @@ impl PlotWindow {
                                                         if !std::ptr::eq(group, plot.[<category_ $sym>]) {
                                                             plot.[<category_ $sym>] = group;
                                                             plot.[<measures_ $sym>].clear();
-                                                            plot.series.clear();
-                                                        }
-                                                        let plot = &mut *plot;
-                                                        for [<$other _measure>] in plot.[<measures_ $other>].iter() {
-                                                            plot.series.insert(PlotSeriesId::new(x_measure, y_measure), PlotSeries::new());
                                                         }
                                                         plot.[<measures_ $sym>].insert([<$sym _measure>]);
                                                     } else {
-                                                        plot.series.retain(|id, _| id.$sym != [<$sym _measure>]);
                                                         plot.[<measures_ $sym>].remove(&[<$sym _measure>]);
                                                     }
+                                                    plot.sync_series();
                                                 }
                                             }
                                         }
@@ -261,15 +792,173 @@ impl PlotWindow {
 
                         axis!("X", x, y);
                         axis!("Y", y, x);
+
+                        ui.menu_button("Objects", |ui| {
+                            let plot = &mut *plot;
+                            let mut changed = false;
+                            let mut toggle = |ui: &mut egui::Ui, sources: &mut Vec<PlotSource>, source: PlotSource, text: String| {
+                                let mut enabled = sources.contains(&source);
+                                if ui.checkbox(&mut enabled, text).changed() {
+                                    if enabled {
+                                        sources.push(source);
+                                    } else {
+                                        sources.retain(|&s| s != source);
+                                    }
+                                    changed = true;
+                                }
+                            };
+                            toggle(ui, &mut plot.sources, PlotSource::This, format!("This object (#{})", parent.index()));
+                            let others = plot.plotted_bodies(parent).filter(|&ent| ent != parent).collect_vec();
+                            for ent in others {
+                                toggle(ui, &mut plot.sources, PlotSource::Body(ent), format!("#{}", ent.index()));
+                            }
+                            ui.separator();
+                            toggle(ui, &mut plot.sources, PlotSource::Plotted, "Total of these objects".to_string());
+                            toggle(ui, &mut plot.sources, PlotSource::Scene, "Total of the scene".to_string());
+                            if let Some(sel) = selected.filter(|&sel| {
+                                sel != parent && !plot.sources.contains(&PlotSource::Body(sel))
+                            }) {
+                                if ui.button(format!("Add selected object (#{})", sel.index())).clicked() {
+                                    plot.sources.push(PlotSource::Body(sel));
+                                    changed = true;
+                                }
+                            }
+                            ui.separator();
+                            match plot.reference {
+                                Some(reference) => ui.label(format!("Reference: #{}", reference.index())),
+                                None => ui.label("No reference object"),
+                            };
+                            if let Some(sel) = selected.filter(|&sel| Some(sel) != plot.reference) {
+                                if ui.button(format!("Use selected object as reference (#{})", sel.index())).clicked() {
+                                    plot.reference = Some(sel);
+                                    changed = true;
+                                }
+                            }
+                            if plot.reference.is_some() && ui.button("Clear reference").clicked() {
+                                plot.reference = None;
+                                changed = true;
+                            }
+                            if changed {
+                                plot.sync_series();
+                            }
+                        });
+                    });
+                    if let Some(status) = plot.export_status.clone() {
+                        ui.horizontal(|ui| {
+                            ui.label(status);
+                            if ui.small_button("OK").clicked() {
+                                plot.export_status = None;
+                            }
+                        });
+                    }
+                    let fitted = ui
+                        .collapsing("Analysis", |ui| plot.show_analysis(ui, id))
+                        .body_returned
+                        .flatten();
+                    // shift-dragging selects the analyzed range instead of panning
+                    let (shift, pressed, down) = ui.input(|i| {
+                        (i.modifiers.shift, i.pointer.primary_pressed(), i.pointer.primary_down())
                     });
-                    Plot::new("plot")
+                    let response = Plot::new("plot")
                         .label_formatter(fmt)
+                        .allow_drag(!shift && plot.analysis.selecting.is_none())
                         .show(ui, |plot_ui| {
+                            // shaded over the data rather than the view, not to widen the
+                            // automatic bounds
+                            let (bottom, top) = plot
+                                .series
+                                .values()
+                                .flat_map(|s| s.values.iter().map(|p| p.y))
+                                .minmax()
+                                .into_option()
+                                .unwrap_or((0.0, 0.0));
+                            if let Some((from, to)) = plot.analysis.range {
+                                plot_ui.polygon(
+                                    Polygon::new(PlotPoints::new(vec![
+                                        [from, bottom],
+                                        [to, bottom],
+                                        [to, top],
+                                        [from, top],
+                                    ]))
+                                    .fill_alpha(0.1)
+                                    .width(0.0),
+                                );
+                                plot_ui.vline(VLine::new(from).style(LineStyle::dotted_dense()));
+                                plot_ui.vline(VLine::new(to).style(LineStyle::dotted_dense()));
+                            }
                             for (name, series) in &plot.series {
-                                plot_ui.line(Line::new(PlotPoints::Owned(series.values.clone())).name(name));
+                                plot_ui.line(Line::new(series.drawn_points()).name(name));
+                            }
+                            if let Some((fit, (from, to))) = fitted {
+                                plot_ui.line(
+                                    Line::new(PlotPoints::from_explicit_callback(
+                                        move |x| fit.eval(x),
+                                        from..=to,
+                                        256,
+                                    ))
+                                    .style(LineStyle::dashed_loose())
+                                    .name("Fit"),
+                                );
                             }
+                            (
+                                plot_ui.plot_bounds(),
+                                plot_ui.pointer_coordinate(),
+                                plot_ui.plot_hovered(),
+                            )
                         });
+                    let (bounds, pointer, hovered) = response.inner;
+                    let analysis = &mut plot.analysis;
+                    analysis.visible = (bounds.min()[0], bounds.max()[0]);
+                    match (analysis.selecting, pointer) {
+                        (None, Some(pointer)) if shift && pressed && hovered => {
+                            analysis.selecting = Some(pointer.x);
+                        }
+                        (Some(start), Some(pointer)) if down => {
+                            analysis.range = Some((start.min(pointer.x), start.max(pointer.x)));
+                        }
+                        _ => {}
+                    }
+                    if !down {
+                        analysis.selecting = None;
+                    }
                 });
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn speed_series() -> HashMap<PlotSeriesId, PlotSeries> {
+        let mut series = PlotSeries::new();
+        for i in 0..10 {
+            series.push(PlotPoint::new(i as f64, 2.0 * i as f64), 100);
+        }
+        HashMap::from([(
+            PlotSeriesId::new(&PLOT_QUANTITIES[0][0], &PLOT_QUANTITIES[2][0], PlotSource::This),
+            series,
+        )])
+    }
+
+    #[test]
+    fn hover_label_of_a_series() {
+        let series = speed_series();
+        let label = hover_label(&series, "Speed / Time", &PlotPoint::new(8.0, 16.0));
+        assert!(label.starts_with("x = 8.00 (Time)\ny = 16.00 (Speed)"), "{}", label);
+        assert!(label.contains("dy/dx = 2.00"), "{}", label);
+        assert!(label.contains("∫dt = 64.00"), "{}", label);
+    }
+
+    #[test]
+    fn hover_label_of_the_fitted_curve() {
+        let series = speed_series();
+        let label = hover_label(&series, "Fit", &PlotPoint::new(1.5, 3.0));
+        assert_eq!(label, "Fit\nx = 1.50\ny = 3.00");
+    }
+
+    #[test]
+    fn hover_label_away_from_lines() {
+        assert_eq!(hover_label(&speed_series(), "", &PlotPoint::new(0.0, 0.0)), "");
+    }
+}
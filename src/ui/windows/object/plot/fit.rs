@@ -0,0 +1,301 @@
+use bevy_egui::egui::plot::PlotPoint;
+use std::f64::consts::{PI, TAU};
+use strum::{EnumIter, IntoStaticStr};
+
+#[derive(EnumIter, IntoStaticStr, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FitModel {
+    Linear,
+    Quadratic,
+    Exponential,
+    #[strum(serialize = "Sinusoidal")]
+    Sine,
+}
+
+pub struct Fit {
+    model: FitModel,
+    /// Linear: a, b. Quadratic: a, b, c. Exponential: a, k. Sine: amplitude, ω, φ, offset.
+    coefficients: Vec<f64>,
+    pub r_squared: f64,
+}
+
+impl Fit {
+    pub fn eval(&self, x: f64) -> f64 {
+        let c = &self.coefficients;
+        match self.model {
+            FitModel::Linear => c[0] * x + c[1],
+            FitModel::Quadratic => (c[0] * x + c[1]) * x + c[2],
+            FitModel::Exponential => c[0] * (c[1] * x).exp(),
+            FitModel::Sine => c[0] * (c[1] * x + c[2]).sin() + c[3],
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        let c = &self.coefficients;
+        match self.model {
+            FitModel::Linear => format!("y = {:.4}·x + {:.4}", c[0], c[1]),
+            FitModel::Quadratic => format!("y = {:.4}·x² + {:.4}·x + {:.4}", c[0], c[1], c[2]),
+            FitModel::Exponential => format!("y = {:.4}·exp({:.4}·x)", c[0], c[1]),
+            FitModel::Sine => format!(
+                "y = {:.4}·sin({:.4}·x + {:.4}) + {:.4}\nperiod = {:.4}",
+                c[0],
+                c[1],
+                c[2],
+                c[3],
+                TAU / c[1]
+            ),
+        }
+    }
+}
+
+pub struct Stats {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    /// Average time between two upward crossings of the mean
+    pub period: Option<f64>,
+}
+
+pub fn stats(points: &[PlotPoint]) -> Option<Stats> {
+    if points.is_empty() {
+        return None;
+    }
+    let min = points.iter().map(|p| p.y).fold(f64::INFINITY, f64::min);
+    let max = points.iter().map(|p| p.y).fold(f64::NEG_INFINITY, f64::max);
+    let mean = points.iter().map(|p| p.y).sum::<f64>() / points.len() as f64;
+    Some(Stats {
+        min,
+        max,
+        mean,
+        period: period(points, mean),
+    })
+}
+
+fn period(points: &[PlotPoint], mean: f64) -> Option<f64> {
+    let crossings: Vec<f64> = points
+        .windows(2)
+        .filter(|w| w[0].y < mean && w[1].y >= mean)
+        .map(|w| w[0].x + (mean - w[0].y) / (w[1].y - w[0].y) * (w[1].x - w[0].x))
+        .collect();
+    if crossings.len() < 2 {
+        return None;
+    }
+    Some((crossings[crossings.len() - 1] - crossings[0]) / (crossings.len() - 1) as f64)
+}
+
+/// Solves `a · x = b` by Gaussian elimination with partial pivoting.
+fn solve<const N: usize>(mut a: [[f64; N]; N], mut b: [f64; N]) -> Option<[f64; N]> {
+    // pivots are compared to the magnitude of the matrix, whatever the units of the data
+    let tolerance = 1e-12 * a.iter().flatten().fold(0.0, |max: f64, v| max.max(v.abs()));
+    for col in 0..N {
+        let pivot = (col..N).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() <= tolerance {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        for row in col + 1..N {
+            let factor = a[row][col] / a[col][col];
+            for k in col..N {
+                a[row][k] -= factor * a[col][k];
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+    let mut x = [0.0; N];
+    for row in (0..N).rev() {
+        let sum: f64 = (row + 1..N).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Some(x)
+}
+
+/// Coefficients of the linear combination of `basis` closest to the points.
+fn least_squares<const N: usize>(
+    points: impl Iterator<Item = (f64, f64)>,
+    basis: impl Fn(f64) -> [f64; N],
+) -> Option<[f64; N]> {
+    let mut a = [[0.0; N]; N];
+    let mut b = [0.0; N];
+    for (x, y) in points {
+        let f = basis(x);
+        for i in 0..N {
+            for j in 0..N {
+                a[i][j] += f[i] * f[j];
+            }
+            b[i] += f[i] * y;
+        }
+    }
+    solve(a, b)
+}
+
+fn sum_squares(points: &[PlotPoint], model: impl Fn(f64) -> f64) -> f64 {
+    points.iter().map(|p| (p.y - model(p.x)).powi(2)).sum()
+}
+
+fn fit_sine(points: &[PlotPoint], omega: f64) -> Option<[f64; 3]> {
+    least_squares(points.iter().map(|p| (p.x, p.y)), |x| {
+        [(omega * x).sin(), (omega * x).cos(), 1.0]
+    })
+}
+
+fn sine_error(points: &[PlotPoint], omega: f64) -> f64 {
+    match fit_sine(points, omega) {
+        Some([s, c, offset]) => sum_squares(points, |x| {
+            s * (omega * x).sin() + c * (omega * x).cos() + offset
+        }),
+        None => f64::INFINITY,
+    }
+}
+
+/// Coefficients in x of a model fitted against u = (x - offset) / scale.
+fn unscale(model: FitModel, c: Vec<f64>, offset: f64, scale: f64) -> Vec<f64> {
+    match model {
+        FitModel::Linear => {
+            let a = c[0] / scale;
+            vec![a, c[1] - a * offset]
+        }
+        FitModel::Quadratic => {
+            let a = c[0] / (scale * scale);
+            let b = c[1] / scale;
+            vec![a, b - 2.0 * a * offset, (a * offset - b) * offset + c[2]]
+        }
+        FitModel::Exponential => {
+            let k = c[1] / scale;
+            vec![c[0] * (-k * offset).exp(), k]
+        }
+        FitModel::Sine => {
+            let omega = c[1] / scale;
+            let phase = (c[2] - omega * offset + PI).rem_euclid(TAU) - PI;
+            vec![c[0], omega, phase, c[3]]
+        }
+    }
+}
+
+pub fn fit(model: FitModel, points: &[PlotPoint]) -> Option<Fit> {
+    if points.len() < 3 {
+        return None;
+    }
+    // the normal equations are solved with x centered and scaled to [-1, 1], otherwise data
+    // recorded far from x = 0 (late in the simulation) makes them ill-conditioned
+    let (min, max) = points
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), p| (min.min(p.x), max.max(p.x)));
+    let offset = (min + max) / 2.0;
+    let scale = if max > min { (max - min) / 2.0 } else { 1.0 };
+    let scaled: Vec<PlotPoint> = points
+        .iter()
+        .map(|p| PlotPoint::new((p.x - offset) / scale, p.y))
+        .collect();
+    let coefficients = unscale(model, fit_scaled(model, &scaled)?, offset, scale);
+    let mut fit = Fit {
+        model,
+        coefficients,
+        r_squared: 0.0,
+    };
+    let mean = points.iter().map(|p| p.y).sum::<f64>() / points.len() as f64;
+    let total = sum_squares(points, |_| mean);
+    let residual = sum_squares(points, |x| fit.eval(x));
+    fit.r_squared = if total > 0.0 { 1.0 - residual / total } else { 1.0 };
+    Some(fit)
+}
+
+fn fit_scaled(model: FitModel, points: &[PlotPoint]) -> Option<Vec<f64>> {
+    let xy = || points.iter().map(|p| (p.x, p.y));
+    let coefficients = match model {
+        FitModel::Linear => least_squares(xy(), |x| [x, 1.0])?.to_vec(),
+        FitModel::Quadratic => least_squares(xy(), |x| [x * x, x, 1.0])?.to_vec(),
+        FitModel::Exponential => {
+            // fit the logarithm, the curve must keep the same sign
+            let sign = if points.iter().all(|p| p.y > 0.0) {
+                1.0
+            } else if points.iter().all(|p| p.y < 0.0) {
+                -1.0
+            } else {
+                return None;
+            };
+            let [k, ln_a] =
+                least_squares(points.iter().map(|p| (p.x, (p.y * sign).ln())), |x| [x, 1.0])?;
+            vec![sign * ln_a.exp(), k]
+        }
+        FitModel::Sine => {
+            let mean = points.iter().map(|p| p.y).sum::<f64>() / points.len() as f64;
+            let mut omega = TAU / period(points, mean)?;
+            // refine the frequency around the estimate, the other parameters are linear
+            let mut span = omega * 0.2;
+            for _ in 0..3 {
+                omega = (-20..=20)
+                    .map(|i| omega + span * i as f64 / 20.0)
+                    .filter(|&w| w > 0.0)
+                    .min_by(|&a, &b| sine_error(points, a).total_cmp(&sine_error(points, b)))?;
+                span /= 10.0;
+            }
+            let [s, c, offset] = fit_sine(points, omega)?;
+            vec![s.hypot(c), omega, c.atan2(s), offset]
+        }
+    };
+    Some(coefficients)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(from: f64, to: f64, count: usize, f: impl Fn(f64) -> f64) -> Vec<PlotPoint> {
+        (0..count)
+            .map(|i| from + (to - from) * i as f64 / (count - 1) as f64)
+            .map(|x| PlotPoint::new(x, f(x)))
+            .collect()
+    }
+
+    fn assert_coefficients(fit: &Fit, expected: &[f64], tolerance: f64) {
+        assert_eq!(fit.coefficients.len(), expected.len());
+        for (actual, expected) in fit.coefficients.iter().zip(expected) {
+            assert!(
+                (actual - expected).abs() <= tolerance * expected.abs().max(1.0),
+                "got {:?}, expected {:?}",
+                fit.coefficients,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn line_far_from_the_origin() {
+        let points = sample(1000.0, 1010.0, 100, |x| 3.0 * x - 2.0);
+        let fit = fit(FitModel::Linear, &points).unwrap();
+        assert_coefficients(&fit, &[3.0, -2.0], 1e-6);
+        assert!(fit.r_squared > 0.999_999);
+    }
+
+    #[test]
+    fn parabola() {
+        let points = sample(-2.0, 10.0, 50, |x| 0.5 * x * x - 3.0 * x + 1.0);
+        let fit = fit(FitModel::Quadratic, &points).unwrap();
+        assert_coefficients(&fit, &[0.5, -3.0, 1.0], 1e-9);
+    }
+
+    #[test]
+    fn exponential() {
+        let points = sample(0.0, 10.0, 50, |x| 2.0 * (0.3 * x).exp());
+        let fit = fit(FitModel::Exponential, &points).unwrap();
+        assert_coefficients(&fit, &[2.0, 0.3], 1e-9);
+
+        let negative = sample(0.0, 10.0, 50, |x| -2.0 * (-0.3 * x).exp());
+        let fit = super::fit(FitModel::Exponential, &negative).unwrap();
+        assert_coefficients(&fit, &[-2.0, -0.3], 1e-9);
+    }
+
+    #[test]
+    fn sine() {
+        let points = sample(0.0, 20.0, 2000, |x| 1.5 * (2.0 * x + 0.5).sin() + 0.3);
+        let fit = fit(FitModel::Sine, &points).unwrap();
+        assert_coefficients(&fit, &[1.5, 2.0, 0.5, 0.3], 1e-6);
+        assert!(fit.r_squared > 0.999);
+    }
+
+    #[test]
+    fn single_abscissa_is_rejected() {
+        let points: Vec<_> = (0..10).map(|i| PlotPoint::new(5.0, i as f64)).collect();
+        assert!(fit(FitModel::Linear, &points).is_none());
+    }
+}
@@ -1,7 +1,97 @@
-use bevy::prelude::Component;
+use crate::scripting::{ScriptComponent, ScriptEngine, SCRIPT_HANDLERS, SCRIPT_PROPERTIES};
+use crate::ui::{InitialPos, Subwindow};
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
 use crate::systems;
 
+systems!(ScriptMenuWindow::show);
+
+const SCRIPT_TEMPLATE: &str = "fn on_collide(other) {\n    this.hue += 0.1;\n}\n";
+
 #[derive(Default, Component)]
-pub struct ScriptMenuWindow;
+pub struct ScriptMenuWindow {
+    /// Source being edited, not applied yet
+    draft: Option<String>,
+    /// Compilation error of the draft
+    error: Option<String>,
+}
+
+impl ScriptMenuWindow {
+    pub fn show(
+        mut wnds: Query<(Entity, &Parent, &mut InitialPos, &mut ScriptMenuWindow)>,
+        mut ents: Query<Option<&mut ScriptComponent>>,
+        engine: Res<ScriptEngine>,
+        mut egui_ctx: EguiContexts,
+        mut commands: Commands,
+    ) {
+        let ctx = egui_ctx.ctx_mut();
+        for (id, parent, mut initial_pos, mut wnd) in wnds.iter_mut() {
+            let Ok(mut script) = ents.get_mut(parent.get()) else { continue };
+            let wnd = &mut *wnd;
+            let draft = wnd.draft.get_or_insert_with(|| match &script {
+                Some(script) => script.source.clone(),
+                None => SCRIPT_TEMPLATE.to_string(),
+            });
+
+            egui::Window::new("Script")
+                .resizable(true)
+                .default_size(egui::Vec2::new(400.0, 300.0))
+                .subwindow(id, ctx, &mut initial_pos, &mut commands, |ui, commands| {
+                    ui.add(
+                        egui::TextEdit::multiline(draft)
+                            .code_editor()
+                            .desired_rows(12)
+                            .desired_width(f32::INFINITY),
+                    );
+
+                    ui.horizontal(|ui| {
+                        if ui.button("Apply").clicked() {
+                            match engine.compile(draft) {
+                                Ok(ast) => {
+                                    wnd.error = None;
+                                    commands
+                                        .entity(parent.get())
+                                        .insert(ScriptComponent::new(draft.clone(), ast));
+                                }
+                                Err(err) => wnd.error = Some(err),
+                            }
+                        }
+                        if script.is_some() && ui.button("Remove").clicked() {
+                            commands.entity(parent.get()).remove::<ScriptComponent>();
+                        }
+                    });
+
+                    if let Some(err) = &wnd.error {
+                        ui.colored_label(egui::Color32::RED, err);
+                    }
+
+                    if let Some(script) = script.as_deref_mut() {
+                        if let Some(err) = &script.error {
+                            ui.colored_label(egui::Color32::RED, format!("Stopped: {}", err));
+                            if ui.button("Restart").clicked() {
+                                script.error = None;
+                            }
+                        } else {
+                            let handlers = script.handlers();
+                            if handlers.is_empty() {
+                                ui.label("No handlers defined");
+                            } else {
+                                ui.label(format!("Running: {}", handlers.join(", ")));
+                            }
+                        }
+                    }
 
-systems!();
\ No newline at end of file
+                    ui.collapsing("Help", |ui| {
+                        ui.label("Handlers:");
+                        for (name, arg) in SCRIPT_HANDLERS {
+                            ui.monospace(format!("fn {}({})", name, arg));
+                        }
+                        ui.label("Properties of this:");
+                        ui.monospace(SCRIPT_PROPERTIES.join(", "));
+                        ui.label("Functions:");
+                        ui.monospace("spawn_box(x, y, w, h)\nspawn_ball(x, y, r)\ndespawn(id)\nprint(text)");
+                    });
+                });
+        }
+    }
+}
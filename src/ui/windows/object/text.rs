@@ -1,7 +1,91 @@
-use bevy::prelude::Component;
+use crate::objects::text::TextObject;
+use crate::objects::SizeComponent;
+use crate::tools::add_object::AddObjectEvent;
+use crate::ui::windows::object::plot::{Measure, PLOT_QUANTITIES};
+use crate::ui::{InitialPos, Subwindow};
+use bevy::math::Vec3Swizzles;
+use bevy::prelude::*;
+use bevy_egui::egui::Ui;
+use bevy_egui::{egui, EguiContexts};
 use crate::systems;
 
+systems!(TextWindow::show);
+
+/// Edits a text object, or the labels attached to a body
 #[derive(Default, Component)]
 pub struct TextWindow;
 
-systems!();
\ No newline at end of file
+fn edit_text(ui: &mut Ui, id: Entity, text: &mut TextObject, size: &mut SizeComponent) {
+    ui.text_edit_multiline(&mut text.text);
+    ui.add(
+        egui::Slider::new(&mut size.0, 0.05..=5.0)
+            .logarithmic(true)
+            .suffix("m")
+            .text("Size :")
+            .custom(),
+    );
+    ui.horizontal(|ui| {
+        ui.label("Live value:");
+        egui::ComboBox::from_id_source((id, "text expression"))
+            .selected_text(text.expression.as_deref().unwrap_or("None"))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut text.expression, None, "None");
+                for group in PLOT_QUANTITIES {
+                    ui.separator();
+                    for quantity in group.iter().filter(|q| matches!(q.measure, Measure::Body(_))) {
+                        ui.selectable_value(
+                            &mut text.expression,
+                            Some(quantity.name.to_string()),
+                            quantity.name,
+                        );
+                    }
+                }
+            });
+    });
+    if text.expression.is_some() {
+        ui.add(egui::Slider::new(&mut text.decimals, 0..=6).text("Decimals"));
+    }
+}
+
+impl TextWindow {
+    pub fn show(
+        mut wnds: Query<(Entity, &Parent, &mut InitialPos), With<TextWindow>>,
+        mut texts: Query<(&mut TextObject, &mut SizeComponent)>,
+        children: Query<&Children>,
+        xforms: Query<&Transform>,
+        mut add_obj: EventWriter<AddObjectEvent>,
+        mut egui_ctx: EguiContexts,
+        mut commands: Commands,
+    ) {
+        let ctx = egui_ctx.ctx_mut();
+        for (id, parent, mut initial_pos) in wnds.iter_mut() {
+            let parent = parent.get();
+            let is_label = texts.contains(parent);
+            let labels: Vec<_> = if is_label {
+                vec![parent]
+            } else {
+                children
+                    .get(parent)
+                    .map(|kids| kids.iter().copied().filter(|&kid| texts.contains(kid)).collect())
+                    .unwrap_or_default()
+            };
+            egui::Window::new("Text")
+                .resizable(false)
+                .default_size(egui::Vec2::ZERO)
+                .subwindow(id, ctx, &mut initial_pos, &mut commands, |ui, _commands| {
+                    for (i, &label) in labels.iter().enumerate() {
+                        if i > 0 {
+                            ui.separator();
+                        }
+                        let (mut text, mut size) = texts.get_mut(label).unwrap();
+                        edit_text(ui, label, &mut text, &mut size);
+                    }
+                    if !is_label && ui.button("Add label").clicked() {
+                        if let Ok(xform) = xforms.get(parent) {
+                            add_obj.send(AddObjectEvent::Text(xform.translation.xy()));
+                        }
+                    }
+                });
+        }
+    }
+}
@@ -1,7 +1,139 @@
-use bevy::prelude::Component;
+use crate::objects::LockedVelocity;
+use crate::ui::dial::Dial;
+use crate::ui::{InitialPos, Subwindow};
+use bevy::math::Vec2;
+use bevy::prelude::{Commands, Component, Entity, Parent, Query};
+use bevy_egui::{egui, EguiContexts};
+use bevy_rapier2d::dynamics::Velocity;
 use crate::systems;
 
+systems!(VelocitiesWindow::show);
+
 #[derive(Default, Component)]
-pub struct VelocitiesWindow;
+pub struct VelocitiesWindow {
+    /// Edit the linear velocity as speed and direction instead of x/y
+    polar: bool,
+    /// Direction of the velocity, rad, kept while the speed is zero
+    angle: f32,
+}
+
+impl VelocitiesWindow {
+    pub fn show(
+        mut wnds: Query<(Entity, &Parent, &mut InitialPos, &mut VelocitiesWindow)>,
+        mut ents: Query<(&mut Velocity, Option<&LockedVelocity>)>,
+        mut egui_ctx: EguiContexts,
+        mut commands: Commands,
+    ) {
+        let ctx = egui_ctx.ctx_mut();
+        for (id, parent, mut initial_pos, mut wnd) in wnds.iter_mut() {
+            let Ok((mut velocity, lock)) = ents.get_mut(parent.get()) else { continue };
+            // only write back on change, Rapier overwrites the body's velocity otherwise
+            let mut vel = *velocity;
+            let mut locked = lock.is_some();
+            let mut changed = false;
+            egui::Window::new("Velocities")
+                .resizable(false)
+                .default_size(egui::Vec2::ZERO)
+                .subwindow(id, ctx, &mut initial_pos, &mut commands, |ui, _commands| {
+                    ui.horizontal(|ui| {
+                        ui.selectable_value(&mut wnd.polar, false, "x / y");
+                        ui.selectable_value(&mut wnd.polar, true, "Speed / angle");
+                    });
+
+                    egui::Grid::new("velocities grid").show(ui, |ui| {
+                        if wnd.polar {
+                            let mut speed = vel.linvel.length();
+                            // a zero velocity has no direction, use the last one chosen
+                            let mut angle = if vel.linvel == Vec2::ZERO {
+                                wnd.angle
+                            } else {
+                                vel.linvel.y.atan2(vel.linvel.x)
+                            };
+                            ui.label("Speed");
+                            let speed_changed = ui
+                                .add(
+                                    egui::DragValue::new(&mut speed)
+                                        .clamp_range(0.0..=f32::INFINITY)
+                                        .speed(0.05)
+                                        .suffix(" m/s"),
+                                )
+                                .changed();
+                            ui.end_row();
+
+                            ui.label("Angle");
+                            let mut degrees = angle.to_degrees();
+                            let mut angle_changed = ui
+                                .add(
+                                    egui::DragValue::new(&mut degrees)
+                                        .clamp_range(-180.0..=180.0)
+                                        .suffix("°"),
+                                )
+                                .changed();
+                            if angle_changed {
+                                angle = degrees.to_radians();
+                            }
+                            angle_changed |= ui.add(Dial::new(&mut angle, 48.0)).changed();
+                            ui.end_row();
+                            wnd.angle = angle;
+
+                            if speed_changed || angle_changed {
+                                vel.linvel = Vec2::from_angle(angle) * speed;
+                                changed = true;
+                            }
+                        } else {
+                            ui.label("Velocity (x)");
+                            changed |= ui
+                                .add(egui::DragValue::new(&mut vel.linvel.x).speed(0.05).suffix(" m/s"))
+                                .changed();
+                            ui.end_row();
+
+                            ui.label("Velocity (y)");
+                            changed |= ui
+                                .add(egui::DragValue::new(&mut vel.linvel.y).speed(0.05).suffix(" m/s"))
+                                .changed();
+                            ui.end_row();
+                        }
+
+                        ui.label("Angular velocity");
+                        changed |= ui
+                            .add(egui::DragValue::new(&mut vel.angvel).speed(0.05).suffix(" rad/s"))
+                            .changed();
+                        ui.end_row();
+                    });
+
+                    ui.horizontal(|ui| {
+                        if ui.button("Stop").clicked() {
+                            vel = Velocity::zero();
+                            changed = true;
+                        }
+                        if ui.button("Stop moving").clicked() {
+                            vel.linvel = Vec2::ZERO;
+                            changed = true;
+                        }
+                        if ui.button("Stop rotating").clicked() {
+                            vel.angvel = 0.0;
+                            changed = true;
+                        }
+                    });
+
+                    ui.checkbox(&mut locked, "Keep velocity constant");
+                });
 
-systems!();
\ No newline at end of file
+            if changed {
+                *velocity = vel;
+            }
+            match (locked, lock) {
+                (true, Some(_)) if changed => {
+                    commands.entity(parent.get()).insert(LockedVelocity(vel));
+                }
+                (true, None) => {
+                    commands.entity(parent.get()).insert(LockedVelocity(vel));
+                }
+                (false, Some(_)) => {
+                    commands.entity(parent.get()).remove::<LockedVelocity>();
+                }
+                _ => {}
+            }
+        }
+    }
+}
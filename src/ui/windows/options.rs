@@ -7,8 +7,9 @@ use bevy_egui::egui::ecolor::HsvaGamma;
 use bevy_egui::egui::{Color32, Stroke};
 use bevy_egui::egui::epaint::Shadow;
 use num_traits::Inv;
-use strum::EnumIter;
+use strum::{EnumIter, IntoEnumIterator};
 use crate::config::AppConfig;
+use crate::objects::laser::FresnelModel;
 use crate::skin::SkinConfig;
 use crate::ui::{InitialPos, Subwindow, tabs};
 
@@ -78,6 +79,46 @@ impl OptionsWindow {
                                 changed = true;
                             }
 
+                            ui.separator();
+
+                            for (value, text, suffix) in [
+                                (&mut app_obj.force_scale, "Force scale:", "m/N"),
+                                (&mut app_obj.velocity_scale, "Velocity scale:", "s"),
+                                (&mut app_obj.momentum_scale, "Momentum scale:", "m/(kg·m/s)"),
+                            ] {
+                                if ui.add(
+                                    egui::Slider::new(value, 0.001..=10.0)
+                                        .logarithmic(true)
+                                        .suffix(suffix)
+                                        .text(text)
+                                        .custom(),
+                                ).changed() {
+                                    changed = true;
+                                }
+                            }
+
+                            if ui.checkbox(&mut app_obj.vector_labels, "Vector labels").changed() {
+                                changed = true;
+                            }
+
+                            ui.separator();
+
+                            let fresnel_name: &'static str = app_obj.fresnel.into();
+                            egui::ComboBox::from_label("Laser reflection")
+                                .selected_text(fresnel_name)
+                                .show_ui(ui, |ui| {
+                                    for model in FresnelModel::iter() {
+                                        let name: &'static str = model.into();
+                                        if ui.selectable_value(&mut app_obj.fresnel, model, name).changed() {
+                                            changed = true;
+                                        }
+                                    }
+                                });
+
+                            if ui.checkbox(&mut app_obj.laser_angles, "Laser normals and angles").changed() {
+                                changed = true;
+                            }
+
                             if changed {
                                 *app = app_obj;
                             }
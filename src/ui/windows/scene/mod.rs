@@ -1,5 +1,6 @@
 use crate::systems;
 
 systems! {
-    mod background
+    mod background,
+    mod statistics,
 }
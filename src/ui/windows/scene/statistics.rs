@@ -0,0 +1,182 @@
+use crate::measures::{GravityEnergy, KineticEnergy, Momentum, SimulationTime};
+use crate::ui::{InitialPos, Subwindow, UiState};
+use crate::{systems, InvTransformPoint};
+use bevy::prelude::*;
+use bevy_egui::egui::plot::{Line, Plot, PlotPoints};
+use bevy_egui::egui::{Color32, Ui};
+use bevy_egui::{egui, EguiContexts};
+use bevy_rapier2d::dynamics::ReadMassProperties;
+use itertools::Itertools;
+
+systems!(StatisticsWindow::show);
+
+/// Energy samples kept for the drift chart
+const HISTORY_LENGTH: usize = 2000;
+
+/// Relative drift above which the total energy is highlighted
+const DRIFT_WARNING: f64 = 0.01;
+
+#[derive(Default, Component)]
+pub struct StatisticsWindow {
+    /// Objects taken into account, the whole scene if empty
+    bodies: Vec<Entity>,
+    /// Total energy over time
+    history: Vec<[f64; 2]>,
+    /// Total energy when the measure started, kept when the history is trimmed
+    initial_energy: Option<f64>,
+    last_step: u64,
+}
+
+#[derive(Default)]
+struct Totals {
+    mass: f32,
+    kinetic: f32,
+    gravity: f32,
+    momentum: Vec2,
+    /// Around the center of mass
+    angular_momentum: f32,
+    center_of_mass: Vec2,
+}
+
+impl Totals {
+    fn energy(&self) -> f32 {
+        self.kinetic + self.gravity
+    }
+}
+
+type StatsQuery<'a> = (
+    &'a ReadMassProperties,
+    &'a Transform,
+    &'a KineticEnergy,
+    &'a GravityEnergy,
+    &'a Momentum,
+);
+
+fn totals(bodies: &[StatsQuery]) -> Totals {
+    let mut totals = Totals::default();
+    let mut weighted = Vec2::ZERO;
+    for &(ReadMassProperties(mass), xform, kinetic, gravity, momentum) in bodies {
+        totals.mass += mass.mass;
+        totals.kinetic += kinetic.total();
+        totals.gravity += gravity.energy;
+        totals.momentum += momentum.linear;
+        weighted += xform.to_global(mass.local_center_of_mass) * mass.mass;
+    }
+    if totals.mass > 0.0 {
+        totals.center_of_mass = weighted / totals.mass;
+    }
+    for &(ReadMassProperties(mass), xform, _, _, momentum) in bodies {
+        let offset = xform.to_global(mass.local_center_of_mass) - totals.center_of_mass;
+        totals.angular_momentum += offset.perp_dot(momentum.linear) + momentum.angular;
+    }
+    totals
+}
+
+impl StatisticsWindow {
+    /// Starts measuring the drift again from the next sample
+    fn reset(&mut self) {
+        self.history.clear();
+        self.initial_energy = None;
+    }
+
+    pub fn show(
+        mut wnds: Query<(Entity, &mut InitialPos, &mut StatisticsWindow)>,
+        ents: Query<StatsQuery>,
+        sim: Res<SimulationTime>,
+        ui_state: Res<UiState>,
+        mut egui_ctx: EguiContexts,
+        mut commands: Commands,
+    ) {
+        let ctx = egui_ctx.ctx_mut();
+        let selected = ui_state
+            .selected_entity
+            .map(|sel| sel.entity)
+            .filter(|&ent| ents.contains(ent));
+        for (id, mut initial_pos, mut wnd) in wnds.iter_mut() {
+            wnd.bodies.retain(|&ent| ents.contains(ent));
+            let bodies = if wnd.bodies.is_empty() {
+                ents.iter().collect_vec()
+            } else {
+                ents.iter_many(&wnd.bodies).collect_vec()
+            };
+            let totals = totals(&bodies);
+
+            if sim.steps != wnd.last_step {
+                wnd.last_step = sim.steps;
+                let energy = totals.energy() as f64;
+                wnd.initial_energy.get_or_insert(energy);
+                wnd.history.push([sim.time as f64, energy]);
+                if wnd.history.len() > HISTORY_LENGTH {
+                    let excess = wnd.history.len() - HISTORY_LENGTH;
+                    wnd.history.drain(..excess);
+                }
+            }
+
+            egui::Window::new("Statistics")
+                .resizable(false)
+                .subwindow(id, ctx, &mut initial_pos, &mut commands, |ui, _commands| {
+                    ui.horizontal(|ui| {
+                        if wnd.bodies.is_empty() {
+                            ui.label("Whole scene");
+                        } else {
+                            ui.label(format!("{} objects", wnd.bodies.len()));
+                            if ui.button("Whole scene").clicked() {
+                                wnd.bodies.clear();
+                                wnd.reset();
+                            }
+                        }
+                        if let Some(sel) = selected.filter(|sel| !wnd.bodies.contains(sel)) {
+                            if ui.button(format!("Add selected object (#{})", sel.index())).clicked() {
+                                wnd.bodies.push(sel);
+                                wnd.reset();
+                            }
+                        }
+                    });
+
+                    fn line(ui: &mut Ui, label: &'static str, val: String) {
+                        ui.label(label);
+                        ui.label(val);
+                        ui.end_row();
+                    }
+                    egui::Grid::new((id, "statistics grid")).striped(true).show(ui, |ui| {
+                        line(ui, "Mass", format!("{:.3} kg", totals.mass));
+                        line(ui, "Kinetic energy", format!("{:.3} J", totals.kinetic));
+                        line(ui, "Potential energy (gravity)", format!("{:.3} J", totals.gravity));
+                        line(ui, "Energy (total)", format!("{:.3} J", totals.energy()));
+                        line(
+                            ui,
+                            "Momentum",
+                            format!("[x={:.3}, y={:.3}] kg·m/s", totals.momentum.x, totals.momentum.y),
+                        );
+                        line(ui, "Angular momentum", format!("{:.3} kg·m²/s", totals.angular_momentum));
+                        line(
+                            ui,
+                            "Center of mass",
+                            format!("[x={:.3}, y={:.3}] m", totals.center_of_mass.x, totals.center_of_mass.y),
+                        );
+                    });
+
+                    let Some(initial) = wnd.initial_energy else { return };
+                    let drift = (totals.energy() as f64 - initial) / initial.abs().max(1e-6);
+                    let text = format!("Energy drift: {:+.2} %", drift * 100.0);
+                    if drift.abs() > DRIFT_WARNING {
+                        ui.colored_label(Color32::RED, text);
+                    } else {
+                        ui.label(text);
+                    }
+                    if ui.button("Reset").clicked() {
+                        wnd.reset();
+                    }
+                    Plot::new((id, "energy drift"))
+                        .height(120.0)
+                        .show_x(false)
+                        .allow_scroll(false)
+                        .show(ui, |plot_ui| {
+                            plot_ui.line(
+                                Line::new(PlotPoints::new(wnd.history.clone())).name("Energy (J)"),
+                            );
+                        });
+                });
+        }
+    }
+}